@@ -1,10 +1,20 @@
 use near_sdk::{env, serde::Serialize};
+use crate::CardsContract;
 
 /// Emit event for logging - generic function for any serializable event
 pub fn emit_event<T: Serialize>(event: T) {
     env::log_str(&format!("EVENT_JSON:{}", serde_json::to_string(&event).unwrap()));
 }
 
+/// Emit a contract event tagged with the next `event_seq`, so indexers can detect missed logs
+pub fn emit_sequenced<T: Serialize>(contract: &mut CardsContract, event: T) {
+    contract.event_seq += 1;
+    emit_event(SequencedEvent {
+        event_seq: contract.event_seq,
+        event,
+    });
+}
+
 /// Log a simple message event (for quick debugging/tracking)
 pub fn log_event(event_type: &str, message: &str) {
     let simple_event = SimpleEvent {
@@ -28,6 +38,14 @@ pub fn log_error(error: &str, context: &str, account_id: Option<near_sdk::Accoun
     emit_event(error_event);
 }
 
+/// Event payload with its contract-wide sequence number
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct SequencedEvent<T> {
+    event_seq: u64,
+    event: T,
+}
+
 /// Simple event structure for basic logging
 #[derive(Serialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -62,4 +80,28 @@ mod tests {
         // This test just verifies the function doesn't panic
         log_error("test error", "test context", None);
     }
+
+    #[test]
+    fn test_sequenced_event_increments() {
+        use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+        use near_sdk::testing_env;
+        use crate::game::{BlackjackEvent, GameState};
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let mut contract = CardsContract::new(accounts(0));
+        assert_eq!(contract.get_current_event_seq(), 0);
+
+        for _ in 0..2 {
+            emit_sequenced(&mut contract, BlackjackEvent::GameStateChanged {
+                old_state: GameState::WaitingForPlayers,
+                new_state: GameState::Betting,
+                timestamp: 0,
+            });
+        }
+        assert_eq!(contract.get_current_event_seq(), 2);
+
+        let logs = get_logs();
+        assert!(logs[0].starts_with(r#"EVENT_JSON:{"event_seq":1,"event":{"GameStateChanged":"#));
+        assert!(logs[1].starts_with(r#"EVENT_JSON:{"event_seq":2,"event":{"GameStateChanged":"#));
+    }
 }
\ No newline at end of file
@@ -1,13 +1,19 @@
-use near_sdk::{env, log, require, AccountId};
-use crate::{CardsContract, events::emit_event};
+use near_sdk::{env, log, require, AccountId, NearToken};
+use crate::{CardsContract, events::emit_sequenced};
 use super::types::*;
 
 // ========================================
 // HELPER FUNCTIONS
 // ========================================
 
-/// Burn tokens for a player (helper function)
-fn burn_tokens_for_player(contract: &mut CardsContract, player_account: &AccountId, amount: u128) {
+/// Burn tokens for a player and append the burn to their persistent history (helper function)
+fn burn_tokens_for_player(
+    contract: &mut CardsContract,
+    player_account: &AccountId,
+    amount: u128,
+    burn_type: BurnType,
+    hand_index: u8,
+) -> BurnRecord {
     // Burn tokens from user account
     let mut user_account = contract.accounts.get(player_account)
         .expect("User account not found");
@@ -27,6 +33,94 @@ fn burn_tokens_for_player(contract: &mut CardsContract, player_account: &Account
     contract.blackjack_stats.total_tokens_burned_betting = 
         contract.blackjack_stats.total_tokens_burned_betting.checked_add(amount)
             .expect("Betting burn stats overflow");
+
+    let record = BurnRecord {
+        burn_type,
+        amount,
+        hand_index,
+        timestamp: env::block_timestamp(),
+    };
+    let mut history = contract.burn_history.get(player_account).unwrap_or_default();
+    if history.len() >= MAX_BURN_HISTORY_RECORDS {
+        history.remove(0);
+    }
+    history.push(record.clone());
+    contract.burn_history.insert(player_account, &history);
+
+    record
+}
+
+/// Refund previously burned tokens to a player (reverses burn_tokens_for_player)
+pub fn refund_burned_tokens(contract: &mut CardsContract, player_account: &AccountId, amount: u128) {
+    let mut user_account = contract.accounts.get(player_account)
+        .expect("User account not found");
+    
+    user_account.balance = user_account.balance.checked_add(amount)
+        .expect("Balance overflow on refund");
+    user_account.total_burned = user_account.total_burned.saturating_sub(amount);
+    
+    contract.accounts.insert(player_account, &user_account);
+
+    // Update contract stats
+    contract.total_supply = contract.total_supply.checked_add(amount)
+        .expect("Total supply overflow");
+    contract.total_cards_burned = contract.total_cards_burned.saturating_sub(amount);
+    contract.blackjack_stats.total_tokens_burned_betting =
+        contract.blackjack_stats.total_tokens_burned_betting.saturating_sub(amount);
+}
+
+/// Reject actions that would grow the seat's vectors past their caps
+fn assert_within_seat_caps(contract: &CardsContract, player: &SeatPlayer, new_hands: usize, new_burns: usize) {
+    let max_hands = contract.game_config.max_hands as usize;
+    require!(
+        player.hands.len() + new_hands <= max_hands,
+        format!("Hand limit reached (max {} per seat)", max_hands)
+    );
+    require!(
+        player.burns_tracking.len() + new_burns <= 2 * max_hands,
+        format!("Burn limit reached (max {} per round)", 2 * max_hands)
+    );
+}
+
+/// Grow the seat's storage reservation if the configuration now needs more than was
+/// reserved at take_seat, rejecting the action when the deposit cannot cover it
+fn ensure_seat_storage(contract: &mut CardsContract, player_account: &AccountId) {
+    let required = crate::storage::calculate_blackjack_player_storage_cost(
+        player_account,
+        contract.game_config.max_hands,
+    );
+    let reserved = contract.seat_storage_reserved.get(player_account).unwrap_or(NearToken::from_near(0));
+    if required <= reserved {
+        return;
+    }
+    
+    let deposit = contract.storage_deposits.get(player_account).unwrap_or(NearToken::from_near(0));
+    let locked = crate::tokens::locked_storage(contract, player_account);
+    require!(
+        deposit >= locked.saturating_add(required.saturating_sub(reserved)),
+        "Insufficient storage deposit for the current seat configuration"
+    );
+    contract.seat_storage_reserved.insert(player_account, &required);
+}
+
+/// Reject burns that would take the seat past the configured per-round total
+fn assert_within_burn_cap(contract: &CardsContract, player: &SeatPlayer, new_burn: u128) {
+    if let Some(max_burn) = contract.game_config.max_burn_per_round {
+        require!(
+            player.total_burned_this_round + new_burn <= max_burn,
+            format!("Round burn limit exceeded (max {} per round)", max_burn)
+        );
+    }
+}
+
+/// Reject a second bet/move from the same seat within one block (when enabled)
+fn assert_one_action_per_block(contract: &CardsContract, player: &SeatPlayer) {
+    if contract.game_config.one_action_per_block {
+        require!(
+            player.last_action_block != Some(env::block_height()),
+            "Only one action per block allowed"
+        );
+    }
 }
 
 // ========================================
@@ -40,7 +134,7 @@ pub fn place_bet(contract: &mut CardsContract, amount: u128) -> bool {
 
     // 1. Validate bet amount
     require!(
-        contract.config.valid_burn_amounts.contains(&amount),
+        contract.config.valid_bet_amounts.contains(&amount),
         "Invalid bet amount"
     );
 
@@ -59,6 +153,10 @@ pub fn place_bet(contract: &mut CardsContract, amount: u128) -> bool {
     };
 
     // 3. Validate game state
+    require!(
+        contract.game_state != GameState::DealingInitialCards,
+        "Betting has closed, cards are being dealt"
+    );
     require!(contract.game_state == GameState::Betting, "Game not in betting state");
 
     // 4. Get and validate player
@@ -72,9 +170,16 @@ pub fn place_bet(contract: &mut CardsContract, amount: u128) -> bool {
 
     require!(player.state == PlayerState::Active, "Player not active");
     require!(player.total_burned_this_round == 0, "Player already bet this round");
+    assert_within_burn_cap(contract, &player, amount);
+    assert_one_action_per_block(contract, &player);
+    let mut pending_bets = contract.pending_bets.get(&seat_number).unwrap_or_default();
+    if pending_bets.len() >= contract.game_config.max_pending_signals as usize {
+        log!("Seat {} has {} unprocessed bet signals - bet rejected until cleanup", seat_number, pending_bets.len());
+        return false;
+    }
 
     // 5. Burn tokens
-    burn_tokens_for_player(contract, &player_account, amount);
+    let burn_record = burn_tokens_for_player(contract, &player_account, amount, BurnType::Bet, 1);
 
     // 6. Create initial hand
     player.hands = vec![PlayerHand {
@@ -87,13 +192,9 @@ pub fn place_bet(contract: &mut CardsContract, amount: u128) -> bool {
         result: None,
     }];
     player.total_burned_this_round = amount;
-    player.burns_tracking = vec![BurnRecord {
-        burn_type: BurnType::Bet,
-        amount,
-        hand_index: 1,
-        timestamp,
-    }];
+    player.burns_tracking = vec![burn_record];
     player.last_action_time = timestamp;
+    player.last_action_block = Some(env::block_height());
 
     // 7. Update seat
     contract.seats.insert(&seat_number, &Some(player));
@@ -108,7 +209,6 @@ pub fn place_bet(contract: &mut CardsContract, amount: u128) -> bool {
         timestamp,
     };
 
-    let mut pending_bets = contract.pending_bets.get(&seat_number).unwrap_or_default();
     pending_bets.push(bet_signal);
     contract.pending_bets.insert(&seat_number, &pending_bets);
 
@@ -116,7 +216,7 @@ pub fn place_bet(contract: &mut CardsContract, amount: u128) -> bool {
     contract.last_activity = timestamp;
 
     // 10. Emit event
-    emit_event(BlackjackEvent::BetPlaced {
+    emit_sequenced(contract, BlackjackEvent::BetPlaced {
         account_id: player_account.clone(),
         amount,
         seat_number,
@@ -127,8 +227,50 @@ pub fn place_bet(contract: &mut CardsContract, amount: u128) -> bool {
     true
 }
 
+/// Cancel your bet before the deal, minting the burned tokens back
+pub fn cancel_bet(contract: &mut CardsContract) -> bool {
+    let player_account = env::predecessor_account_id();
+    let timestamp = env::block_timestamp();
+
+    require!(contract.game_state == GameState::Betting, "Game not in betting state");
+
+    let seat_number = crate::game::player::is_player_seated(contract, &player_account)
+        .expect("Player not seated");
+    let mut player = contract.seats.get(&seat_number).flatten()
+        .expect("Player not found at seat");
+
+    let amount = player.total_burned_this_round;
+    require!(amount > 0, "No bet to cancel this round");
+
+    // Mint the bet back and reverse the burn stats
+    refund_burned_tokens(contract, &player_account, amount);
+
+    player.hands.clear();
+    player.burns_tracking.clear();
+    player.total_burned_this_round = 0;
+    player.last_action_time = timestamp;
+    contract.seats.insert(&seat_number, &Some(player));
+
+    // Drop the bet signals the backend hasn't consumed yet
+    let mut pending_bets = contract.pending_bets.get(&seat_number).unwrap_or_default();
+    pending_bets.retain(|signal| signal.player_account != player_account);
+    contract.pending_bets.insert(&seat_number, &pending_bets);
+
+    contract.last_activity = timestamp;
+
+    emit_sequenced(contract, BlackjackEvent::BetCancelled {
+        account_id: player_account.clone(),
+        amount,
+        seat_number,
+        timestamp,
+    });
+
+    log!("Player {} cancelled bet of {} at seat {}", player_account, amount, seat_number);
+    true
+}
+
 /// Signal a move 
-pub fn signal_move(contract: &mut CardsContract, move_type: PlayerMove, hand_index: u8) -> bool {
+pub fn signal_move(contract: &mut CardsContract, move_type: PlayerMove, hand_index: u8, nonce: Option<u64>) -> bool {
     let player_account = env::predecessor_account_id();
     let timestamp = env::block_timestamp();
 
@@ -156,6 +298,20 @@ pub fn signal_move(contract: &mut CardsContract, move_type: PlayerMove, hand_ind
     // 3. Check if it's this player's turn (redundant but kept for safety)
     require!(contract.current_player_seat == Some(seat_number), "Not your turn");
 
+    // Idempotency: a retried move carries the nonce of the one already accepted
+    let mut recent_nonces = contract.move_nonces.get(&seat_number).unwrap_or_default();
+    if let Some(nonce) = nonce {
+        if recent_nonces.contains(&nonce) {
+            log!("Duplicate move nonce {} from {} ignored", nonce, player_account);
+            return false;
+        }
+    }
+    let mut pending_moves = contract.pending_moves.get(&seat_number).unwrap_or_default();
+    if pending_moves.len() >= contract.game_config.max_pending_signals as usize {
+        log!("Seat {} has {} unprocessed move signals - move rejected until cleanup", seat_number, pending_moves.len());
+        return false;
+    }
+
     // 4. Get and validate player
     let mut player = match contract.seats.get(&seat_number) {
         Some(Some(p)) => p,
@@ -165,8 +321,10 @@ pub fn signal_move(contract: &mut CardsContract, move_type: PlayerMove, hand_ind
         }
     };
 
+    assert_one_action_per_block(contract, &player);
+
     // 5. Validate hand index
-    require!(hand_index >= 1 && hand_index <= 2, "Invalid hand index (must be 1 or 2)");
+    require!((1..=2).contains(&hand_index), "Invalid hand index (must be 1 or 2)");
     require!(hand_index == player.current_hand_index, "Must play current hand index");
 
     let hand_idx = (hand_index - 1) as usize;
@@ -184,16 +342,22 @@ pub fn signal_move(contract: &mut CardsContract, move_type: PlayerMove, hand_ind
             hand.can_hit = false;
         }
         PlayerMove::Double => {
+            if player.hands[0].has_split && !contract.game_config.allow_double_after_split {
+                log!("Double rejected for {}: doubling after a split is not allowed at this table", player_account);
+                return false;
+            }
             require!(!player.hands[hand_idx].has_doubled, "Cannot double twice on same hand");
             require!(player.hands[hand_idx].can_hit, "Cannot double on finished hand");
+            assert_within_seat_caps(contract, &player, 0, 1);
             
             let double_amount = player.hands[hand_idx].bet_amount;
+            assert_within_burn_cap(contract, &player, double_amount);
             require!(
                 crate::tokens::get_balance(contract, &player_account) >= double_amount,
                 "Insufficient tokens for double"
             );
             
-            burn_tokens_for_player(contract, &player_account, double_amount);
+            let burn_record = burn_tokens_for_player(contract, &player_account, double_amount, BurnType::Double, hand_index);
             
             let hand = &mut player.hands[hand_idx];
             hand.has_doubled = true;
@@ -202,25 +366,23 @@ pub fn signal_move(contract: &mut CardsContract, move_type: PlayerMove, hand_ind
             hand.bet_amount += double_amount;
             
             player.total_burned_this_round += double_amount;
-            player.burns_tracking.push(BurnRecord {
-                burn_type: BurnType::Double,
-                amount: double_amount,
-                hand_index,
-                timestamp,
-            });
+            player.burns_tracking.push(burn_record);
         }
         PlayerMove::Split => {
             require!(hand_index == 1, "Can only split on hand 1");
             require!(!player.hands[hand_idx].has_split, "Cannot split twice");
             require!(player.hands.len() == 1, "Cannot split when already have multiple hands");
+            assert_within_seat_caps(contract, &player, 1, 1);
+            ensure_seat_storage(contract, &player_account);
             
             let split_amount = player.hands[hand_idx].bet_amount;
+            assert_within_burn_cap(contract, &player, split_amount);
             require!(
                 crate::tokens::get_balance(contract, &player_account) >= split_amount,
                 "Insufficient tokens for split"
             );
             
-            burn_tokens_for_player(contract, &player_account, split_amount);
+            let burn_record = burn_tokens_for_player(contract, &player_account, split_amount, BurnType::Split, 2);
             
             player.hands[hand_idx].has_split = true;
             
@@ -237,53 +399,233 @@ pub fn signal_move(contract: &mut CardsContract, move_type: PlayerMove, hand_ind
             
             player.current_hand_index = 2;
             player.total_burned_this_round += split_amount;
-            player.burns_tracking.push(BurnRecord {
-                burn_type: BurnType::Split,
-                amount: split_amount,
-                hand_index: 2,
-                timestamp,
-            });
+            player.burns_tracking.push(burn_record);
         }
     }
 
     // 7. Handle hand completion logic
-    if player.hands[hand_idx].is_finished {
-        if hand_index == 2 && player.hands.len() > 1 && !player.hands[0].is_finished {
-            player.current_hand_index = 1;
-        }
+    if player.hands[hand_idx].is_finished
+        && hand_index == 2
+        && player.hands.len() > 1
+        && !player.hands[0].is_finished
+    {
+        player.current_hand_index = 1;
     }
 
-    // 8. Update seat
+    // 8. Update seat. A dealer result computed before this hand changed is stale
+    if move_type != PlayerMove::Stand && contract.pending_settlement.take().is_some() {
+        log!("Pending dealer result dropped after {:?} by {}", move_type, player_account);
+    }
     player.last_action_time = timestamp;
+    player.last_action_block = Some(env::block_height());
     contract.seats.insert(&seat_number, &Some(player));
 
     // 9. Create move signal
     let move_signal = MoveSignal {
         player_account: player_account.clone(),
         seat_number,
-        move_type: move_type.clone(),
+        move_type,
         hand_index,
         timestamp,
     };
 
-    let mut pending_moves = contract.pending_moves.get(&seat_number).unwrap_or_default();
     pending_moves.push(move_signal);
     contract.pending_moves.insert(&seat_number, &pending_moves);
+    if let Some(nonce) = nonce {
+        if recent_nonces.len() >= MAX_RECENT_MOVE_NONCES {
+            recent_nonces.remove(0);
+        }
+        recent_nonces.push(nonce);
+        contract.move_nonces.insert(&seat_number, &recent_nonces);
+    }
 
     // 10. Update global state
     contract.last_activity = timestamp;
 
     // 11. Emit event
-    emit_event(BlackjackEvent::MoveSignaled {
+    emit_sequenced(contract, BlackjackEvent::MoveSignaled {
         account_id: player_account.clone(),
         move_type,
         timestamp,
     });
 
     log!("Player {} made move {:?} on hand {} at seat {}", player_account, move_type, hand_index, seat_number);
+
+    // 12. Heads-up rounds settle as soon as the last hand is finished
+    if !try_auto_settle(contract, seat_number) {
+        // 13. Otherwise hand the turn on when enabled
+        try_auto_advance(contract, seat_number);
+    }
+    true
+}
+
+/// Move to the next seat with a bet (or the dealer) once the current seat finished every hand
+fn try_auto_advance(contract: &mut CardsContract, seat_number: u8) -> bool {
+    if !contract.game_config.auto_advance_turns {
+        return false;
+    }
+
+    let all_hands_finished = match contract.seats.get(&seat_number) {
+        Some(Some(player)) => !player.hands.is_empty() && player.hands.iter().all(|hand| hand.is_finished),
+        _ => false,
+    };
+    if !all_hands_finished {
+        return false;
+    }
+
+    // Seats play in order - wrapping back to an earlier seat means everyone has played
+    let next_state = match crate::game::player::find_next_active_player(contract, seat_number)
+        .filter(|next_seat| *next_seat > seat_number)
+    {
+        Some(2) => GameState::Seat2Turn,
+        Some(3) => GameState::Seat3Turn,
+        _ => GameState::DealerTurn,
+    };
+
+    log!("Seat {} finished - auto-advancing to {:?}", seat_number, next_state);
+    crate::game::admin::advance_game_state(contract, next_state)
+}
+
+/// Store the backend's dealer result for a single-player round.
+/// Settles immediately if the player has already finished all hands.
+pub fn submit_dealer_result(contract: &mut CardsContract, distribution: WinningsDistribution) -> bool {
+    require!(
+        contract.game_config.auto_settle_single_player,
+        "Single-player auto-settle is disabled"
+    );
+
+    let seat_number = match crate::game::player::single_seated_player(contract) {
+        Some(seat) => seat,
+        None => {
+            log!("Auto-settle requires exactly one seated player");
+            return false;
+        }
+    };
+
+    require!(
+        distribution.distributions.iter().all(|winning| winning.seat_number == seat_number),
+        "Dealer result can only settle the seated player"
+    );
+
+    log!("Dealer result submitted for round {} at seat {}", distribution.round_number, seat_number);
+    contract.pending_settlement = Some(distribution);
+
+    try_auto_settle(contract, seat_number);
     true
 }
 
+/// Settle with the pending dealer result once the only seated player has finished every hand
+fn try_auto_settle(contract: &mut CardsContract, seat_number: u8) -> bool {
+    if !contract.game_config.auto_settle_single_player
+        || crate::game::player::single_seated_player(contract) != Some(seat_number)
+    {
+        return false;
+    }
+
+    let all_hands_finished = match contract.seats.get(&seat_number) {
+        Some(Some(player)) => !player.hands.is_empty() && player.hands.iter().all(|hand| hand.is_finished),
+        _ => false,
+    };
+    if !all_hands_finished {
+        return false;
+    }
+
+    match contract.pending_settlement.take() {
+        Some(distribution) => {
+            // The hands may have changed since the result was submitted
+            if let Err(reason) = check_distribution(contract, &distribution) {
+                log!("Pending dealer result discarded: {}", reason);
+                return false;
+            }
+            log!("Auto-settling single-player round {}", distribution.round_number);
+            distribute_winnings(contract, distribution)
+        }
+        None => false,
+    }
+}
+
+/// The seat's player for this round: the seated one, or one who left after cards were dealt
+fn round_player(contract: &CardsContract, seat_number: u8, account_id: &AccountId) -> Option<SeatPlayer> {
+    contract.seats.get(&seat_number).flatten()
+        .filter(|player| &player.account_id == account_id)
+        .or_else(|| contract.departed_players.get(&seat_number).filter(|player| &player.account_id == account_id))
+}
+
+/// Remove and return every player kept for settlement after leaving mid-round
+pub fn take_departed_players(contract: &mut CardsContract) -> Vec<SeatPlayer> {
+    (1..=3).filter_map(|seat| contract.departed_players.remove(&seat)).collect()
+}
+
+/// Run every distribute_winnings check without touching state
+pub fn check_distribution(contract: &CardsContract, distribution: &WinningsDistribution) -> Result<(), String> {
+    // 1. Round number (safety check)
+    if distribution.round_number < contract.round_number {
+        return Err("Cannot distribute winnings for past rounds".to_string());
+    }
+
+    // 2. Validate every payout against the seat's burned bet and the configured rules
+    let mut settled_hands: Vec<(u8, u8)> = Vec::new();
+    for winning in &distribution.distributions {
+        let player = round_player(contract, winning.seat_number, &winning.account_id)
+            .ok_or_else(|| format!("Seat {} has no round for {}", winning.seat_number, winning.account_id))?;
+        let hand = winning.hand_index.checked_sub(1)
+            .and_then(|index| player.hands.get(index as usize))
+            .ok_or_else(|| format!("Seat {} has no hand {}", winning.seat_number, winning.hand_index))?;
+        if winning.bet_amount != hand.bet_amount {
+            return Err(format!("Bet for seat {} hand {} must be {}, got {}",
+                winning.seat_number, winning.hand_index, hand.bet_amount, winning.bet_amount));
+        }
+        if settled_hands.contains(&(winning.seat_number, winning.hand_index)) {
+            return Err(format!("Seat {} hand {} is settled twice", winning.seat_number, winning.hand_index));
+        }
+        settled_hands.push((winning.seat_number, winning.hand_index));
+
+        // A push always returns exactly the burned stake
+        if winning.result == HandResult::Push && winning.winnings() != winning.bet_amount {
+            return Err(format!("Push for {} must return exactly the stake: expected {}, got {}",
+                winning.account_id, winning.bet_amount, winning.winnings()));
+        }
+        let expected = contract.game_config.expected_payout(winning.bet_amount, winning.result);
+        if winning.winnings() != expected {
+            return Err(format!("Invalid payout for {} ({:?}): expected {}, got {}",
+                winning.account_id, winning.result, expected, winning.winnings()));
+        }
+        // The stake comes back first, anything above it is profit
+        let expected_returned = expected.min(winning.bet_amount);
+        if winning.bet_returned != expected_returned {
+            return Err(format!("Invalid split for {} ({:?}): expected bet_returned {}, got {}",
+                winning.account_id, winning.result, expected_returned, winning.bet_returned));
+        }
+    }
+
+    // Strict mode: every bettor is settled and nobody else is paid
+    if contract.game_config.strict_distribution {
+        let bettors: Vec<(u8, AccountId)> = (1..=3)
+            .flat_map(|seat| [contract.seats.get(&seat).flatten(), contract.departed_players.get(&seat)])
+            .flatten()
+            .filter(|player| player.total_burned_this_round > 0)
+            .map(|player| (player.seat_number, player.account_id))
+            .collect();
+        for winning in &distribution.distributions {
+            if !bettors.iter().any(|(seat, account)| *seat == winning.seat_number && *account == winning.account_id) {
+                return Err(format!("Seat {} ({}) has no bet this round", winning.seat_number, winning.account_id));
+            }
+        }
+        for (seat, account) in &bettors {
+            if !distribution.distributions.iter().any(|winning| winning.seat_number == *seat) {
+                return Err(format!("Bet at seat {} ({}) is not settled", seat, account));
+            }
+        }
+    }
+
+    let requested_mint: u128 = distribution.distributions.iter().map(|winning| winning.winnings()).sum();
+    if crate::tokens::is_supply_cap_reached(contract, requested_mint) {
+        return Err("Winnings would exceed max total supply".to_string());
+    }
+
+    Ok(())
+}
+
 /// Distribute winnings by minting tokens (admin only)
 pub fn distribute_winnings(
     contract: &mut CardsContract, 
@@ -291,47 +633,84 @@ pub fn distribute_winnings(
 ) -> bool {
     let timestamp = env::block_timestamp();
 
-    // 1. Update round number (safety check)
-    require!(
-        distribution.round_number >= contract.round_number,
-        "Cannot distribute winnings for past rounds"
-    );
+    // 1-2. Round number, payout rules, strict settlement and supply cap
+    if let Err(reason) = check_distribution(contract, &distribution) {
+        env::panic_str(&reason);
+    }
 
-    // 2. Process each player's winnings
+    // 3. Process each player's winnings
     let mut total_minted = 0u128;
+    let mut total_rake = 0u128;
+    let treasury_registered = contract.accounts.get(&contract.treasury_id).is_some();
     
     for winning in &distribution.distributions {
         // Find player account
         if let Some(mut user_account) = contract.accounts.get(&winning.account_id) {
+            // Rake only the profit, never the returned stake
+            let rake = if treasury_registered {
+                winning.net_profit * contract.game_config.rake_basis_points as u128 / 10_000
+            } else {
+                0
+            };
+            
             // Mint winnings (add to balance)
-            user_account.balance += winning.winnings;
+            user_account.balance += winning.winnings() - rake;
             contract.accounts.insert(&winning.account_id, &user_account);
             
-            total_minted += winning.winnings;
+            if rake > 0 {
+                let treasury_id = contract.treasury_id.clone();
+                let mut treasury = contract.accounts.get(&treasury_id).expect("Treasury account not found");
+                treasury.balance += rake;
+                contract.accounts.insert(&treasury_id, &treasury);
+                total_rake += rake;
+            }
             
-            log!("Winnings distributed: {} received {} tokens (result: {:?})", 
-                winning.account_id, winning.winnings, winning.result);
+            total_minted += winning.winnings();
+            
+            log!("Winnings distributed: {} received {} tokens (result: {:?}, rake: {})", 
+                winning.account_id, winning.winnings() - rake, winning.result, rake);
         } else {
             log!("Warning: Player {} not found for winnings distribution", 
                 winning.account_id);
         }
     }
 
-    // 3. Update contract stats
+    // 4. Update contract stats
     contract.total_supply += total_minted;
-    contract.blackjack_stats.total_winnings_distributed += total_minted;
+    contract.blackjack_stats.total_winnings_distributed += total_minted - total_rake;
+    contract.blackjack_stats.total_rake_collected += total_rake;
     contract.blackjack_stats.total_hands_dealt += distribution.distributions.len() as u64;
 
-    // 4. Reset all players for next round
+    // 5. Record round summary before player state is reset (players who left mid-round still bet)
+    let tokens_burned: u128 = (1..=3)
+        .filter_map(|seat| contract.seats.get(&seat).flatten())
+        .chain((1..=3).filter_map(|seat| contract.departed_players.get(&seat)))
+        .map(|player| player.total_burned_this_round)
+        .sum();
+    contract.round_history.insert(&distribution.round_number, &RoundSummary {
+        round_number: distribution.round_number,
+        tokens_burned,
+        tokens_minted: total_minted,
+        players_count: distribution.distributions.len() as u8,
+        timestamp,
+    });
+
+    // 6. Reset all players for next round
     for seat in 1..=3 {
         if let Some(Some(mut player)) = contract.seats.get(&seat) {
             // Reset to clean state for next round
+            player.record_session_bet(player.total_burned_this_round);
             player.current_hand_index = 1;
             player.hands.clear();
             player.total_burned_this_round = 0;
             player.burns_tracking.clear();
             player.last_action_time = timestamp;
             player.rounds_played += 1;
+            for winning in &distribution.distributions {
+                if winning.seat_number == seat && winning.account_id == player.account_id {
+                    player.record_result(winning.result);
+                }
+            }
             
             // Keep player active if they want to continue
             if player.state == PlayerState::Active {
@@ -342,20 +721,23 @@ pub fn distribute_winnings(
         }
     }
 
-    // 5. Update global game state
+    // 7. Update global game state
     contract.round_number += 1;
     contract.last_activity = timestamp;
     contract.game_state = GameState::WaitingForPlayers; // Ready for next round
     contract.current_player_seat = None;
+    contract.current_move_deadline = None;
+    contract.pending_settlement = None;
+    take_departed_players(contract);
 
-    // 6. Auto-clear all signals since round is complete 
+    // 8. Auto-clear all signals since round is complete 
     for seat_number in 1..=3 {
         contract.pending_bets.insert(&seat_number, &Vec::new());
         contract.pending_moves.insert(&seat_number, &Vec::new());
     }
 
-    // 7. Emit event
-    emit_event(BlackjackEvent::WinningsDistributed {
+    // 9. Emit event
+    emit_sequenced(contract, BlackjackEvent::WinningsDistributed {
         round_number: distribution.round_number,
         total_minted,
         players_count: distribution.distributions.len() as u8,
@@ -1,5 +1,5 @@
-use near_sdk::{env, log, AccountId};
-use crate::{CardsContract, events::emit_event};
+use near_sdk::{env, log, require, AccountId};
+use crate::{CardsContract, events::emit_sequenced};
 use super::types::*;
 
 // ========================================
@@ -10,9 +10,23 @@ use super::types::*;
 pub fn advance_game_state(contract: &mut CardsContract, new_state: GameState) -> bool {
     let timestamp = env::block_timestamp();
     
-    let old_state = contract.game_state.clone();
-    contract.game_state = new_state.clone();
+    let old_state = contract.game_state;
+    require!(
+        old_state.can_transition_to(new_state),
+        format!("Illegal game state transition {:?} -> {:?}", old_state, new_state)
+    );
+    if new_state == GameState::Betting {
+        let ready = count_players_ready_for_round(contract);
+        let required = contract.game_config.min_players_to_start;
+        if ready < required {
+            log!("Cannot open betting: {} of {} required players ready", ready, required);
+            return false;
+        }
+    }
+    contract.game_state = new_state;
     contract.last_activity = timestamp;
+    contract.current_move_deadline = None; // Only seat turns carry a deadline
+    let move_deadline = timestamp + contract.game_config.move_timeout_ms * 1_000_000;
 
     // Handle state-specific logic
     match new_state {
@@ -26,9 +40,9 @@ pub fn advance_game_state(contract: &mut CardsContract, new_state: GameState) ->
                     player.burns_tracking.clear();
                     player.last_action_time = timestamp;
                     
-                    // Activate observing players
-                    if player.state == PlayerState::Observing || 
-                       player.state == PlayerState::WaitingForNextRound {
+                    // Activate waiting players (observers only when auto-activation is on)
+                    if player.state == PlayerState::WaitingForNextRound ||
+                       (player.state == PlayerState::Observing && contract.game_config.auto_activate_observers) {
                         player.state = PlayerState::Active;
                     }
                     
@@ -41,14 +55,17 @@ pub fn advance_game_state(contract: &mut CardsContract, new_state: GameState) ->
 
         GameState::Seat1Turn => {
             contract.current_player_seat = Some(1);
+            contract.current_move_deadline = Some(move_deadline);
         }
 
         GameState::Seat2Turn => {
             contract.current_player_seat = Some(2);
+            contract.current_move_deadline = Some(move_deadline);
         }
 
         GameState::Seat3Turn => {
             contract.current_player_seat = Some(3);
+            contract.current_move_deadline = Some(move_deadline);
         }
 
         GameState::DealerTurn => {
@@ -66,9 +83,9 @@ pub fn advance_game_state(contract: &mut CardsContract, new_state: GameState) ->
     }
 
     // Emit event
-    emit_event(BlackjackEvent::GameStateChanged {
+    emit_sequenced(contract, BlackjackEvent::GameStateChanged {
         old_state,
-        new_state: new_state.clone(),
+        new_state,
         timestamp,
     });
 
@@ -76,6 +93,127 @@ pub fn advance_game_state(contract: &mut CardsContract, new_state: GameState) ->
     true
 }
 
+/// Seated players who will be Active once Betting opens
+fn count_players_ready_for_round(contract: &CardsContract) -> u8 {
+    (1..=3)
+        .filter_map(|seat| contract.seats.get(&seat).flatten())
+        .filter(|player| match player.state {
+            PlayerState::Active | PlayerState::WaitingForNextRound => true,
+            PlayerState::Observing => contract.game_config.auto_activate_observers,
+            _ => false,
+        })
+        .count() as u8
+}
+
+/// Move WaitingForPlayers -> Betting once the auto-start delay has elapsed
+pub fn try_auto_start(contract: &mut CardsContract) -> bool {
+    if contract.game_state != GameState::WaitingForPlayers
+        || crate::game::player::count_active_players(contract) == 0
+    {
+        return false;
+    }
+
+    let start_at = contract.waiting_since + contract.game_config.auto_start_delay_ms * 1_000_000;
+    if env::block_timestamp() < start_at {
+        return false;
+    }
+
+    log!("Auto-starting round {}", contract.round_number);
+    advance_game_state(contract, GameState::Betting)
+}
+
+/// Hand the turn to a specific seat that has a bet in play
+pub fn set_turn(contract: &mut CardsContract, seat_number: u8) -> bool {
+    let turn_state = match seat_number {
+        1 => GameState::Seat1Turn,
+        2 => GameState::Seat2Turn,
+        3 => GameState::Seat3Turn,
+        _ => env::panic_str("Invalid seat number (must be 1-3)"),
+    };
+
+    let player = contract.seats.get(&seat_number).flatten()
+        .unwrap_or_else(|| env::panic_str("Seat is empty"));
+    require!(player.state == PlayerState::Active, "Player at seat is not active");
+    require!(player.total_burned_this_round > 0, "Seat has no bet this round");
+
+    // Sets current_player_seat and a fresh move deadline
+    advance_game_state(contract, turn_state)
+}
+
+/// Record the dealer-computed score of the hand in play. A score over 21 finishes the
+/// hand as `Bust` so further hits are rejected; cards are dealt off-chain
+pub fn report_hand_score(contract: &mut CardsContract, seat_number: u8, hand_index: u8, score: u8) -> bool {
+    let turn_state = match seat_number {
+        1 => GameState::Seat1Turn,
+        2 => GameState::Seat2Turn,
+        3 => GameState::Seat3Turn,
+        _ => env::panic_str("Invalid seat number (must be 1-3)"),
+    };
+    require!(contract.game_state == turn_state, "Not this seat's turn");
+
+    let mut player = contract.seats.get(&seat_number).flatten()
+        .unwrap_or_else(|| env::panic_str("Seat is empty"));
+    require!((1..=2).contains(&hand_index), "Invalid hand index (must be 1 or 2)");
+    let hand_idx = (hand_index - 1) as usize;
+    require!(hand_idx < player.hands.len(), "Hand does not exist");
+    require!(!player.hands[hand_idx].is_finished, "Hand is already finished");
+
+    if score <= 21 {
+        log!("Hand {} at seat {} scored {} - still in play", hand_index, seat_number, score);
+        return false;
+    }
+
+    let hand = &mut player.hands[hand_idx];
+    hand.is_finished = true;
+    hand.can_hit = false;
+    hand.result = Some(HandResult::Bust);
+    if hand_index == 2 && !player.hands[0].is_finished {
+        player.current_hand_index = 1;
+    }
+
+    let account_id = player.account_id.clone();
+    contract.seats.insert(&seat_number, &Some(player));
+
+    emit_sequenced(contract, BlackjackEvent::HandBusted {
+        account_id: account_id.clone(),
+        seat_number,
+        hand_index,
+        score,
+        timestamp: env::block_timestamp(),
+    });
+
+    log!("Hand {} of {} at seat {} busted with {}", hand_index, account_id, seat_number, score);
+    true
+}
+
+/// Close betting, deal the round and emit one summary of all bets placed
+pub fn close_betting(contract: &mut CardsContract) -> bool {
+    require!(contract.game_state == GameState::Betting, "Betting is not open");
+
+    let mut total_pot = 0u128;
+    let mut seats_with_bets = 0u8;
+    for seat in 1..=3 {
+        if let Some(Some(player)) = contract.seats.get(&seat) {
+            if player.total_burned_this_round > 0 {
+                total_pot += player.total_burned_this_round;
+                seats_with_bets += 1;
+            }
+        }
+    }
+
+    advance_game_state(contract, GameState::DealingInitialCards);
+
+    emit_sequenced(contract, BlackjackEvent::BettingClosed {
+        round_number: contract.round_number,
+        total_pot,
+        seats_with_bets,
+        timestamp: env::block_timestamp(),
+    });
+
+    log!("Betting closed for round {} - {} seats, pot {}", contract.round_number, seats_with_bets, total_pot);
+    true
+}
+
 /// Kick player by account ID
 pub fn kick_player(contract: &mut CardsContract, account_id: AccountId, reason: String) -> bool {
     let timestamp = env::block_timestamp();
@@ -94,17 +232,15 @@ pub fn kick_player(contract: &mut CardsContract, account_id: AccountId, reason:
         _ => return false,
     };
 
-    // Handle refunds
-    if player.total_burned_this_round > 0 {
-        if let Some(mut user_account) = contract.accounts.get(&account_id) {
-            user_account.balance += player.total_burned_this_round;
-            contract.accounts.insert(&account_id, &user_account);
-            
-            contract.total_supply += player.total_burned_this_round;
-            contract.blackjack_stats.total_tokens_burned_betting -= player.total_burned_this_round;
-            
-            log!("Refunded {} tokens to kicked player {}", player.total_burned_this_round, account_id);
-        }
+    // Handle refunds (skipped during settlement - the bet is paid out by distribute_winnings)
+    if player.total_burned_this_round > 0
+        && !contract.game_state.is_settlement()
+        && contract.accounts.get(&account_id).is_some()
+    {
+        crate::game::action::refund_burned_tokens(contract, &account_id, player.total_burned_this_round);
+        log!("Refunded {} tokens to kicked player {}", player.total_burned_this_round, account_id);
+    } else if player.total_burned_this_round > 0 {
+        contract.departed_players.insert(&seat_number, &player);
     }
 
     // Adjust current player if necessary
@@ -112,15 +248,19 @@ pub fn kick_player(contract: &mut CardsContract, account_id: AccountId, reason:
         contract.current_player_seat = crate::game::player::find_next_active_player(contract, seat_number);
     }
 
-    // Remove player (clear the entry entirely)
+    // Remove player (clear the entry entirely) and release its storage
     contract.seats.remove(&seat_number);
+    contract.account_to_seat.remove(&account_id);
+    contract.seat_storage_reserved.remove(&account_id);
     contract.last_activity = timestamp;
+    contract.waiting_since = timestamp;
 
     // Clear signals
     contract.pending_bets.insert(&seat_number, &Vec::new());
     contract.pending_moves.insert(&seat_number, &Vec::new());
+    contract.move_nonces.remove(&seat_number);
 
-    emit_event(BlackjackEvent::PlayerLeft {
+    emit_sequenced(contract, BlackjackEvent::PlayerLeft {
         account_id: account_id.clone(),
         seat_number,
         timestamp,
@@ -130,6 +270,220 @@ pub fn kick_player(contract: &mut CardsContract, account_id: AccountId, reason:
     true
 }
 
+/// Refund every seated player's burns for the current round and reset the table
+pub fn emergency_refund(contract: &mut CardsContract, reason: String) -> u8 {
+    let timestamp = env::block_timestamp();
+    let mut players_refunded = 0u8;
+
+    // Refund and reset each seated player
+    for seat in 1..=3 {
+        if let Some(Some(mut player)) = contract.seats.get(&seat) {
+            if player.total_burned_this_round > 0 {
+                crate::game::action::refund_burned_tokens(contract, &player.account_id, player.total_burned_this_round);
+                log!("Emergency refund: {} tokens to {} at seat {}",
+                    player.total_burned_this_round, player.account_id, seat);
+                players_refunded += 1;
+            }
+
+            player.current_hand_index = 1;
+            player.hands.clear();
+            player.total_burned_this_round = 0;
+            player.burns_tracking.clear();
+            player.last_action_time = timestamp;
+
+            contract.seats.insert(&seat, &Some(player));
+        }
+
+        // Clear signals for the abandoned round
+        contract.pending_bets.insert(&seat, &Vec::new());
+        contract.pending_moves.insert(&seat, &Vec::new());
+    }
+
+    // Players who left mid-round get their burns back too
+    for player in crate::game::action::take_departed_players(contract) {
+        if contract.accounts.get(&player.account_id).is_some() {
+            crate::game::action::refund_burned_tokens(contract, &player.account_id, player.total_burned_this_round);
+            log!("Emergency refund: {} tokens to departed {}", player.total_burned_this_round, player.account_id);
+            players_refunded += 1;
+        }
+    }
+
+    // Reset game state
+    contract.game_state = GameState::WaitingForPlayers;
+    contract.current_player_seat = None;
+    contract.current_move_deadline = None;
+    contract.pending_settlement = None;
+    contract.last_activity = timestamp;
+
+    emit_sequenced(contract, BlackjackEvent::EmergencyRefund {
+        reason: reason.clone(),
+        players_refunded,
+        timestamp,
+    });
+
+    log!("Emergency refund completed for {} players - {}", players_refunded, reason);
+    players_refunded
+}
+
+/// Refund a round that closed betting but was never distributed once it has been
+/// idle for `stale_round_timeout_ms` (permissionless escape hatch)
+pub fn refund_stale_round(contract: &mut CardsContract) -> u8 {
+    require!(
+        !matches!(contract.game_state, GameState::WaitingForPlayers | GameState::Betting),
+        "No round in progress"
+    );
+    let stale_at = contract.last_activity
+        .saturating_add(contract.game_config.stale_round_timeout_ms.saturating_mul(1_000_000));
+    require!(env::block_timestamp() >= stale_at, "Round is not stale yet");
+
+    emergency_refund(contract, format!("Stale round {} refunded by {}", contract.round_number, env::predecessor_account_id()))
+}
+
+/// Refund live bets, empty every seat and start over from round 0. Round history is
+/// cleared with it so replayed round numbers never collide with old summaries.
+/// `total_games_played`/`total_hands_dealt` always restart; `keep_totals` preserves
+/// the remaining lifetime stats (burns, winnings, rake, players joined)
+pub fn reset_game(contract: &mut CardsContract, keep_totals: bool) -> u8 {
+    let timestamp = env::block_timestamp();
+    let mut players_refunded = 0u8;
+    let mut players_removed = 0u8;
+
+    for seat in 1..=3 {
+        if let Some(Some(player)) = contract.seats.get(&seat) {
+            if player.total_burned_this_round > 0 && contract.accounts.get(&player.account_id).is_some() {
+                crate::game::action::refund_burned_tokens(contract, &player.account_id, player.total_burned_this_round);
+                log!("Reset refund: {} tokens to {} at seat {}",
+                    player.total_burned_this_round, player.account_id, seat);
+                players_refunded += 1;
+            }
+            contract.account_to_seat.remove(&player.account_id);
+            contract.seat_storage_reserved.remove(&player.account_id);
+            players_removed += 1;
+        }
+
+        contract.seats.remove(&seat);
+        contract.pending_bets.insert(&seat, &Vec::new());
+        contract.pending_moves.insert(&seat, &Vec::new());
+        contract.move_nonces.remove(&seat);
+    }
+
+    for player in crate::game::action::take_departed_players(contract) {
+        if contract.accounts.get(&player.account_id).is_some() {
+            crate::game::action::refund_burned_tokens(contract, &player.account_id, player.total_burned_this_round);
+            log!("Reset refund: {} tokens to departed {}", player.total_burned_this_round, player.account_id);
+            players_refunded += 1;
+        }
+    }
+
+    contract.game_state = GameState::WaitingForPlayers;
+    for round_number in 0..=contract.round_number {
+        contract.round_history.remove(&round_number);
+    }
+    contract.round_number = 0;
+    contract.current_player_seat = None;
+    contract.current_move_deadline = None;
+    contract.pending_settlement = None;
+    contract.last_activity = timestamp;
+    contract.waiting_since = timestamp;
+
+    if keep_totals {
+        contract.blackjack_stats.total_games_played = 0;
+        contract.blackjack_stats.total_hands_dealt = 0;
+    } else {
+        contract.blackjack_stats = crate::BlackjackStats::default();
+    }
+
+    emit_sequenced(contract, BlackjackEvent::GameReset {
+        players_refunded,
+        players_removed,
+        timestamp,
+    });
+
+    log!("Game reset - {} players removed, {} refunded", players_removed, players_refunded);
+    players_refunded
+}
+
+/// Apply a game configuration update (validated as a whole before anything changes)
+pub fn update_game_config(contract: &mut CardsContract, update: GameConfigUpdate) {
+    let mut new_config = contract.game_config.clone();
+    
+    if let Some(value) = update.betting_timeout_ms { new_config.betting_timeout_ms = value; }
+    if let Some(value) = update.move_timeout_ms { new_config.move_timeout_ms = value; }
+    if let Some(value) = update.round_break_ms { new_config.round_break_ms = value; }
+    if let Some(value) = update.max_inactive_time_ms { new_config.max_inactive_time_ms = value; }
+    if let Some(value) = update.min_bet_amount { new_config.min_bet_amount = value; }
+    if let Some(value) = update.max_bet_amount { new_config.max_bet_amount = value; }
+    if let Some(value) = update.auto_start_delay_ms { new_config.auto_start_delay_ms = value; }
+    if let Some(value) = update.max_players { new_config.max_players = Some(value); }
+    if let Some(value) = update.allow_double_after_split { new_config.allow_double_after_split = value; }
+    if let Some(value) = update.max_hands { new_config.max_hands = value; }
+    if let Some(value) = update.min_players_to_start { new_config.min_players_to_start = value; }
+    if let Some(value) = update.max_pending_signals { new_config.max_pending_signals = value; }
+    if let Some(value) = update.stale_round_timeout_ms { new_config.stale_round_timeout_ms = value; }
+    
+    require!(
+        new_config.min_bet_amount <= new_config.max_bet_amount,
+        "min_bet_amount cannot exceed max_bet_amount"
+    );
+    require!(
+        new_config.max_players.is_none_or(|max| (1..=3).contains(&max)),
+        "max_players must be between 1 and 3"
+    );
+    require!(
+        (1..=MAX_HANDS_PER_SEAT as u8).contains(&new_config.max_hands),
+        format!("max_hands must be between 1 and {}", MAX_HANDS_PER_SEAT)
+    );
+    require!(
+        new_config.min_players_to_start <= new_config.max_players.unwrap_or(3),
+        "min_players_to_start cannot exceed max_players"
+    );
+    require!(new_config.max_pending_signals >= 1, "max_pending_signals must be at least 1");
+    require!(
+        new_config.stale_round_timeout_ms >= new_config.move_timeout_ms,
+        "stale_round_timeout_ms cannot be shorter than move_timeout_ms"
+    );
+    // Configured bet denominations must stay inside the bet bounds
+    crate::tokens::validate_bet_amounts(&contract.config.valid_bet_amounts, &new_config);
+    
+    let old = &contract.game_config;
+    let changes = [
+        ("betting_timeout_ms", old.betting_timeout_ms.to_string(), new_config.betting_timeout_ms.to_string()),
+        ("move_timeout_ms", old.move_timeout_ms.to_string(), new_config.move_timeout_ms.to_string()),
+        ("round_break_ms", old.round_break_ms.to_string(), new_config.round_break_ms.to_string()),
+        ("max_inactive_time_ms", old.max_inactive_time_ms.to_string(), new_config.max_inactive_time_ms.to_string()),
+        ("min_bet_amount", old.min_bet_amount.to_string(), new_config.min_bet_amount.to_string()),
+        ("max_bet_amount", old.max_bet_amount.to_string(), new_config.max_bet_amount.to_string()),
+        ("auto_start_delay_ms", old.auto_start_delay_ms.to_string(), new_config.auto_start_delay_ms.to_string()),
+        ("max_players", format!("{:?}", old.max_players), format!("{:?}", new_config.max_players)),
+        ("allow_double_after_split", old.allow_double_after_split.to_string(), new_config.allow_double_after_split.to_string()),
+        ("max_hands", old.max_hands.to_string(), new_config.max_hands.to_string()),
+        ("min_players_to_start", old.min_players_to_start.to_string(), new_config.min_players_to_start.to_string()),
+        ("max_pending_signals", old.max_pending_signals.to_string(), new_config.max_pending_signals.to_string()),
+        ("stale_round_timeout_ms", old.stale_round_timeout_ms.to_string(), new_config.stale_round_timeout_ms.to_string()),
+    ];
+    
+    for (field, old_value, new_value) in changes {
+        if old_value != new_value {
+            record_config_change(contract, field, old_value, new_value);
+        }
+    }
+    
+    contract.game_config = new_config;
+    log!("Game configuration updated by {}", env::predecessor_account_id());
+}
+
+/// Audit a game config change and emit its ConfigUpdate event
+pub fn record_config_change(contract: &mut CardsContract, field: &str, old_value: String, new_value: String) {
+    crate::tokens::append_config_audit(contract, field, old_value.clone(), new_value.clone());
+    emit_sequenced(contract, BlackjackEvent::ConfigUpdate {
+        field: field.to_string(),
+        old_value,
+        new_value,
+        updated_by: env::predecessor_account_id(),
+        timestamp: env::block_timestamp(),
+    });
+}
+
 /// Get detailed admin statistics
 pub fn get_admin_stats(contract: &CardsContract) -> AdminStats {
     let mut total_active_bets = 0u128;
@@ -1,5 +1,5 @@
 use near_sdk::{env, log, AccountId};
-use crate::{CardsContract, events::{emit_event, log_error}};
+use crate::{CardsContract, events::{emit_sequenced, log_error}};
 use super::types::*;
 
 // ========================================
@@ -9,46 +9,100 @@ use super::types::*;
 /// Take a seat (1, 2, or 3)
 pub fn take_seat(contract: &mut CardsContract, seat_number: u8) -> bool {
     let player_account = env::predecessor_account_id();
-    let timestamp = env::block_timestamp();
 
-    // 1. Validate seat number
-    if seat_number < 1 || seat_number > 3 {
-        log_error("Invalid seat number", &format!("Seat {}", seat_number), Some(player_account.clone()));
+    // 1. Check if joining is allowed in current game state
+    if contract.game_state != GameState::WaitingForPlayers {
+        log_error("Cannot join seat", "Can only join seats during WaitingForPlayers state", Some(player_account.clone()));
         return false;
     }
 
-    // 2. Check if joining is allowed in current game state
-    if contract.game_state != GameState::WaitingForPlayers {
-        log_error("Cannot join seat", "Can only join seats during WaitingForPlayers state", Some(player_account.clone()));
+    // 2. Validate seat, existing seating and storage
+    if !can_take_seat(contract, &player_account, seat_number) {
+        return false;
+    }
+
+    // 3. Place player in seat
+    seat_player(contract, &player_account, seat_number);
+    true
+}
+
+/// Take a seat and place the opening bet atomically (Betting state only)
+pub fn join_and_bet(contract: &mut CardsContract, seat_number: u8, amount: u128) -> bool {
+    let player_account = env::predecessor_account_id();
+
+    // 1. Only while betting is open
+    if contract.game_state != GameState::Betting {
+        log_error("Cannot join and bet", "Can only join and bet during Betting state", Some(player_account.clone()));
+        return false;
+    }
+
+    // 2. Validate seat, existing seating and storage
+    if !can_take_seat(contract, &player_account, seat_number) {
+        return false;
+    }
+
+    // 3. Validate bet up front so a failed bet never leaves the player seated
+    if !contract.config.valid_bet_amounts.contains(&amount) {
+        log_error("Invalid bet amount", &format!("Amount {}", amount), Some(player_account.clone()));
+        return false;
+    }
+    if crate::tokens::get_balance(contract, &player_account) < amount {
+        log_error("Insufficient token balance", &format!("Amount {}", amount), Some(player_account.clone()));
+        return false;
+    }
+
+    // 4. Seat and bet - roll the seat back if the bet is not placed
+    seat_player(contract, &player_account, seat_number);
+    if !crate::game::action::place_bet(contract, amount) {
+        contract.seats.remove(&seat_number);
+        contract.account_to_seat.remove(&player_account);
+        contract.seat_storage_reserved.remove(&player_account);
+        contract.blackjack_stats.total_players_joined -= 1;
+        log_error("Bet failed, seat released", &format!("Seat {}", seat_number), Some(player_account.clone()));
+        return false;
+    }
+
+    true
+}
+
+/// Check that the player may take the given seat (logs the reason when not)
+fn can_take_seat(contract: &CardsContract, player_account: &AccountId, seat_number: u8) -> bool {
+    // 1. Validate seat number
+    if !(1..=3).contains(&seat_number) {
+        log_error("Invalid seat number", &format!("Seat {}", seat_number), Some(player_account.clone()));
         return false;
     }
 
-    // 3. Check if seat is available
+    // 2. Check if seat is available
     if contract.seats.get(&seat_number).is_some() {
         log_error("Seat occupied", &format!("Seat {}", seat_number), Some(player_account.clone()));
         return false;
     }
 
-    // 4. Check if player is already seated somewhere
-    for seat in 1..=3 {
-        if let Some(Some(existing_player)) = contract.seats.get(&seat) {
-            if existing_player.account_id == player_account {
-                log_error("Player already seated", &format!("Seat {}", seat), Some(player_account.clone()));
-                return false;
-            }
-        }
+    // 3. Check if player is already seated somewhere
+    if let Some(seat) = is_player_seated(contract, player_account) {
+        log_error("Player already seated", &format!("Seat {}", seat), Some(player_account.clone()));
+        return false;
     }
 
-    // 5. Check storage
+    // 4. Check storage
     if !crate::storage::has_sufficient_blackjack_storage(
-        contract.storage_deposits.get(&player_account).unwrap_or(near_sdk::NearToken::from_near(0)),
-        &player_account
+        contract.storage_deposits.get(player_account).unwrap_or(near_sdk::NearToken::from_near(0)),
+        player_account,
+        contract.game_config.max_hands,
     ) {
         log_error("Insufficient storage for blackjack", "take_seat", Some(player_account.clone()));
         return false;
     }
 
-    // 6. Create seat player
+    true
+}
+
+/// Place a new player in a seat and emit the join event
+fn seat_player(contract: &mut CardsContract, player_account: &AccountId, seat_number: u8) {
+    let timestamp = env::block_timestamp();
+
+    // 1. Create seat player
     let seat_player = SeatPlayer {
         account_id: player_account.clone(),
         seat_number,
@@ -63,23 +117,35 @@ pub fn take_seat(contract: &mut CardsContract, seat_number: u8) -> bool {
         burns_tracking: Vec::new(),
         joined_at: timestamp,
         last_action_time: timestamp,
+        last_action_block: None,
         rounds_played: 0,
+        wins: 0,
+        losses: 0,
+        pushes: 0,
+        blackjacks: 0,
+        session_bets: Vec::new(),
     };
 
-    // 7. Place player in seat
+    // 2. Place player in seat (no longer just watching) and reserve its storage
     contract.seats.insert(&seat_number, &Some(seat_player));
+    contract.account_to_seat.insert(player_account, &seat_number);
+    contract.spectators.remove(player_account);
+    contract.seat_storage_reserved.insert(
+        player_account,
+        &crate::storage::calculate_blackjack_player_storage_cost(player_account, contract.game_config.max_hands),
+    );
     contract.last_activity = timestamp;
+    contract.waiting_since = timestamp;
     contract.blackjack_stats.total_players_joined += 1;
 
-    // 8. Emit event
-    emit_event(BlackjackEvent::PlayerJoined {
+    // 3. Emit event
+    emit_sequenced(contract, BlackjackEvent::PlayerJoined {
         account_id: player_account.clone(),
         seat_number,
         timestamp,
     });
 
     log!("Player {} took seat {}", player_account, seat_number);
-    true
 }
 
 /// Leave your current seat
@@ -106,19 +172,17 @@ pub fn leave_seat(contract: &mut CardsContract) -> bool {
         }
     };
 
-    // 2. Handle refunds if player has active bet
+    // 2. Handle refunds if player has active bet. Only before cards are dealt - once the
+    //    round reaches settlement the bet is paid out by distribute_winnings instead
     if player.total_burned_this_round > 0 && matches!(contract.game_state, GameState::Betting | GameState::WaitingForPlayers) {
         // Refund burned tokens by minting them back
-        if let Some(mut user_account) = contract.accounts.get(&player_account) {
-            user_account.balance += player.total_burned_this_round;
-            contract.accounts.insert(&player_account, &user_account);
-            
-            // Update contract stats
-            contract.total_supply += player.total_burned_this_round;
-            contract.blackjack_stats.total_tokens_burned_betting -= player.total_burned_this_round;
-            
+        if contract.accounts.get(&player_account).is_some() {
+            crate::game::action::refund_burned_tokens(contract, &player_account, player.total_burned_this_round);
             log!("Refunded {} tokens to leaving player {}", player.total_burned_this_round, player_account);
         }
+    } else if player.total_burned_this_round > 0 {
+        // Keep the hands so the settlement can still be checked against them
+        contract.departed_players.insert(&seat_number, &player);
     }
 
     // 3. Adjust current player if necessary
@@ -126,16 +190,20 @@ pub fn leave_seat(contract: &mut CardsContract) -> bool {
         contract.current_player_seat = find_next_active_player(contract, seat_number);
     }
 
-    // 4. Remove player from seat (clear the entry entirely)
+    // 4. Remove player from seat (clear the entry entirely) and release its storage
     contract.seats.remove(&seat_number);
+    contract.account_to_seat.remove(&player_account);
+    contract.seat_storage_reserved.remove(&player_account);
     contract.last_activity = timestamp;
+    contract.waiting_since = timestamp;
 
     // 5. Clear pending signals for this seat
     contract.pending_bets.insert(&seat_number, &Vec::new());
     contract.pending_moves.insert(&seat_number, &Vec::new());
+    contract.move_nonces.remove(&seat_number);
 
     // 6. Emit event
-    emit_event(BlackjackEvent::PlayerLeft {
+    emit_sequenced(contract, BlackjackEvent::PlayerLeft {
         account_id: player_account.clone(),
         seat_number,
         timestamp,
@@ -145,6 +213,131 @@ pub fn leave_seat(contract: &mut CardsContract) -> bool {
     true
 }
 
+/// Move to another empty seat between rounds, keeping the seat's stats
+pub fn change_seat(contract: &mut CardsContract, new_seat: u8) -> bool {
+    let player_account = env::predecessor_account_id();
+    let timestamp = env::block_timestamp();
+
+    // 1. Only between rounds
+    if contract.game_state != GameState::WaitingForPlayers {
+        log_error("Cannot change seat", "Can only change seats during WaitingForPlayers state", Some(player_account.clone()));
+        return false;
+    }
+
+    // 2. Validate the target seat
+    if !(1..=3).contains(&new_seat) {
+        log_error("Invalid seat number", &format!("Seat {}", new_seat), Some(player_account.clone()));
+        return false;
+    }
+    if contract.seats.get(&new_seat).is_some() {
+        log_error("Seat occupied", &format!("Seat {}", new_seat), Some(player_account.clone()));
+        return false;
+    }
+
+    // 3. Find the player's current seat
+    let old_seat = match is_player_seated(contract, &player_account) {
+        Some(seat) => seat,
+        None => {
+            log_error("Player not seated", "change_seat", Some(player_account.clone()));
+            return false;
+        }
+    };
+    let mut player = match contract.seats.get(&old_seat) {
+        Some(Some(p)) => p,
+        _ => return false,
+    };
+
+    // 4. Move the player (stats and storage reservation travel with them)
+    player.seat_number = new_seat;
+    player.last_action_time = timestamp;
+    contract.account_to_seat.insert(&player.account_id, &new_seat);
+    contract.seats.insert(&new_seat, &Some(player));
+    contract.seats.remove(&old_seat);
+    if contract.current_player_seat == Some(old_seat) {
+        contract.current_player_seat = Some(new_seat);
+    }
+    contract.last_activity = timestamp;
+
+    // 5. Clear pending signals for the old seat
+    contract.pending_bets.insert(&old_seat, &Vec::new());
+    contract.pending_moves.insert(&old_seat, &Vec::new());
+    contract.move_nonces.remove(&old_seat);
+
+    emit_sequenced(contract, BlackjackEvent::SeatChanged {
+        account_id: player_account.clone(),
+        old_seat,
+        new_seat,
+        timestamp,
+    });
+
+    log!("Player {} moved from seat {} to seat {}", player_account, old_seat, new_seat);
+    true
+}
+
+/// Opt an observing player in to play from the next betting phase
+pub fn opt_in_next_round(contract: &mut CardsContract) -> bool {
+    let player_account = env::predecessor_account_id();
+
+    let seat_number = match is_player_seated(contract, &player_account) {
+        Some(seat) => seat,
+        None => {
+            log_error("Player not seated", "opt_in_next_round", Some(player_account.clone()));
+            return false;
+        }
+    };
+
+    let mut player = match contract.seats.get(&seat_number) {
+        Some(Some(p)) => p,
+        _ => return false,
+    };
+
+    if player.state != PlayerState::Observing {
+        log_error("Player not observing", &format!("Seat {}", seat_number), Some(player_account.clone()));
+        return false;
+    }
+
+    player.state = PlayerState::WaitingForNextRound;
+    player.last_action_time = env::block_timestamp();
+    contract.seats.insert(&seat_number, &Some(player));
+
+    log!("Player {} at seat {} opted in for next round", player_account, seat_number);
+    true
+}
+
+/// Register as a spectator of the table (seated players are already at the table)
+pub fn watch_game(contract: &mut CardsContract) -> bool {
+    let account_id = env::predecessor_account_id();
+
+    if contract.accounts.get(&account_id).is_none() {
+        log_error("Account not registered", "watch_game", Some(account_id.clone()));
+        return false;
+    }
+    if is_player_seated(contract, &account_id).is_some() {
+        log_error("Seated players cannot spectate", "watch_game", Some(account_id.clone()));
+        return false;
+    }
+    if !contract.spectators.insert(&account_id) {
+        log_error("Already spectating", "watch_game", Some(account_id.clone()));
+        return false;
+    }
+
+    log!("{} is now watching the table", account_id);
+    true
+}
+
+/// Stop spectating the table
+pub fn stop_watching(contract: &mut CardsContract) -> bool {
+    let account_id = env::predecessor_account_id();
+
+    if !contract.spectators.remove(&account_id) {
+        log_error("Not spectating", "stop_watching", Some(account_id.clone()));
+        return false;
+    }
+
+    log!("{} stopped watching the table", account_id);
+    true
+}
+
 // ========================================
 // HELPER FUNCTIONS
 // ========================================
@@ -169,14 +362,18 @@ pub fn get_player_at_seat(contract: &CardsContract, seat_number: u8) -> Option<S
 
 /// Check if player is seated
 pub fn is_player_seated(contract: &CardsContract, player_account: &AccountId) -> Option<u8> {
-    for seat in 1..=3 {
-        if let Some(Some(player)) = contract.seats.get(&seat) {
-            if player.account_id == *player_account {
-                return Some(seat);
-            }
-        }
+    contract.account_to_seat.get(player_account)
+}
+
+/// Get the seat of the only seated player (None if 0 or 2+ seats are occupied)
+pub fn single_seated_player(contract: &CardsContract) -> Option<u8> {
+    let occupied: Vec<u8> = (1..=3)
+        .filter(|seat| contract.seats.get(seat).flatten().is_some())
+        .collect();
+    match occupied.as_slice() {
+        [seat] => Some(*seat),
+        _ => None,
     }
-    None
 }
 
 /// Count active players
@@ -21,6 +21,30 @@ pub enum GameState {
     DealerTurn,
 }
 
+impl GameState {
+    /// Round is being settled - burns are paid out by `distribute_winnings`, never refunded
+    pub fn is_settlement(&self) -> bool {
+        matches!(self, GameState::DealerTurn)
+    }
+
+    /// Legal round flow: WaitingForPlayers -> Betting -> DealingInitialCards ->
+    /// seat turns in ascending order -> DealerTurn -> WaitingForPlayers.
+    /// A betting phase nobody joined may also return to WaitingForPlayers
+    pub fn can_transition_to(&self, next: GameState) -> bool {
+        use GameState::*;
+        matches!(
+            (self, next),
+            (WaitingForPlayers, Betting)
+                | (Betting, DealingInitialCards | WaitingForPlayers)
+                | (DealingInitialCards, Seat1Turn | Seat2Turn | Seat3Turn | DealerTurn)
+                | (Seat1Turn, Seat2Turn | Seat3Turn | DealerTurn)
+                | (Seat2Turn, Seat3Turn | DealerTurn)
+                | (Seat3Turn, DealerTurn)
+                | (DealerTurn, WaitingForPlayers)
+        )
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, Copy, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub enum PlayerState {
@@ -67,9 +91,50 @@ pub struct SeatPlayer {
     pub burns_tracking: Vec<BurnRecord>, // Detailed burn history
     pub joined_at: u64,
     pub last_action_time: u64,
+    pub last_action_block: Option<u64>, // Block height of last bet/move
     pub rounds_played: u32,
+    // Hand results settled during this seat session (Bust counts as a loss)
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub session_bets: Vec<u128>, // Total burned in each settled round, oldest first (capped)
+}
+
+impl SeatPlayer {
+    /// Count a settled hand in the session scoreboard
+    pub fn record_result(&mut self, result: HandResult) {
+        match result {
+            HandResult::Blackjack => self.blackjacks += 1,
+            HandResult::Win => self.wins += 1,
+            HandResult::Push => self.pushes += 1,
+            HandResult::Bust | HandResult::Lose => self.losses += 1,
+        }
+    }
+
+    /// Append a round's total bet, dropping the oldest beyond `MAX_SESSION_BETS`
+    pub fn record_session_bet(&mut self, amount: u128) {
+        if self.session_bets.len() >= MAX_SESSION_BETS {
+            self.session_bets.remove(0);
+        }
+        self.session_bets.push(amount);
+    }
 }
 
+/// Seat vector caps: every move re-serializes the whole SeatPlayer, so these keep it
+/// under `SEAT_PLAYER_MAX_BYTES` (worst case ~1,100 bytes with a 64-char account id).
+/// `GameConfig::max_hands` may lower the hand cap, never raise it
+pub const MAX_HANDS_PER_SEAT: usize = 2;
+pub const MAX_BURNS_PER_ROUND: usize = 2 * MAX_HANDS_PER_SEAT; // Bet or split + a double on each hand
+pub const MAX_SESSION_BETS: usize = 50; // Rounds kept in `session_bets`
+pub const SEAT_PLAYER_MAX_BYTES: usize = 1_280;
+
+/// Client move nonces remembered per seat to reject retried `make_move` calls
+pub const MAX_RECENT_MOVE_NONCES: usize = 16;
+
+/// Maximum rake on winnings profit (10%)
+pub const MAX_RAKE_BPS: u16 = 1_000;
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct PlayerHand {
@@ -86,6 +151,11 @@ pub struct PlayerHand {
 // BURN TRACKING STRUCTURES
 // ======================================
 
+/// Maximum records returned by one burn history query
+pub const MAX_BURN_HISTORY_LIMIT: u64 = 100;
+/// Burn records kept per account; the oldest is dropped once the history is full
+pub const MAX_BURN_HISTORY_RECORDS: usize = 20;
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct BurnRecord {
@@ -140,18 +210,113 @@ pub struct WinningsDistribution {
     pub total_minted: u128,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
-#[serde(crate = "near_sdk::serde")]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde", try_from = "PlayerWinningJson", into = "PlayerWinningJson")]
 pub struct PlayerWinning {
+    pub account_id: AccountId,
+    pub seat_number: u8,
+    pub bet_amount: u128,
+    pub bet_returned: u128, // Part of the stake paid back (the whole bet unless the hand lost)
+    pub net_profit: u128, // Winnings on top of the stake; the only part that is raked
+    pub result: HandResult,
+    pub hand_index: u8,
+}
+
+impl PlayerWinning {
+    /// Total amount to mint (returned stake + profit)
+    pub fn winnings(&self) -> u128 {
+        self.bet_returned.checked_add(self.net_profit).expect("Winnings overflow")
+    }
+}
+
+/// JSON shape of PlayerWinning. Payloads may send `bet_returned` + `net_profit`, the
+/// legacy `winnings` total alone (the stake is returned first), or all three if they agree
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlayerWinningJson {
     #[schemars(with = "String")]
     pub account_id: AccountId,
     pub seat_number: u8,
     pub bet_amount: u128,
-    pub winnings: u128, // Amount to mint (includes bet return)
+    #[serde(default)]
+    pub bet_returned: Option<u128>,
+    #[serde(default)]
+    pub net_profit: Option<u128>,
+    #[serde(default)]
+    pub winnings: Option<u128>, // bet_returned + net_profit
     pub result: HandResult,
     pub hand_index: u8,
 }
 
+impl TryFrom<PlayerWinningJson> for PlayerWinning {
+    type Error = String;
+
+    fn try_from(json: PlayerWinningJson) -> Result<Self, Self::Error> {
+        let (bet_returned, net_profit) = match (json.bet_returned, json.net_profit, json.winnings) {
+            (Some(bet_returned), Some(net_profit), winnings) => {
+                let total = bet_returned.checked_add(net_profit).ok_or("Winnings overflow")?;
+                if winnings.is_some_and(|winnings| winnings != total) {
+                    return Err(format!("winnings must equal bet_returned + net_profit ({})", total));
+                }
+                (bet_returned, net_profit)
+            }
+            (None, None, Some(winnings)) => {
+                let bet_returned = winnings.min(json.bet_amount);
+                (bet_returned, winnings - bet_returned)
+            }
+            _ => return Err("Provide bet_returned and net_profit, or winnings".to_string()),
+        };
+        Ok(Self {
+            account_id: json.account_id,
+            seat_number: json.seat_number,
+            bet_amount: json.bet_amount,
+            bet_returned,
+            net_profit,
+            result: json.result,
+            hand_index: json.hand_index,
+        })
+    }
+}
+
+impl From<PlayerWinning> for PlayerWinningJson {
+    fn from(winning: PlayerWinning) -> Self {
+        Self {
+            winnings: Some(winning.winnings()),
+            account_id: winning.account_id,
+            seat_number: winning.seat_number,
+            bet_amount: winning.bet_amount,
+            bet_returned: Some(winning.bet_returned),
+            net_profit: Some(winning.net_profit),
+            result: winning.result,
+            hand_index: winning.hand_index,
+        }
+    }
+}
+
+impl JsonSchema for PlayerWinning {
+    fn schema_name() -> String {
+        "PlayerWinning".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        PlayerWinningJson::json_schema(gen)
+    }
+}
+
+// ======================================
+// ROUND HISTORY
+// ======================================
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoundSummary {
+    pub round_number: u64,
+    pub tokens_burned: u128, // All burns: bet + double + split
+    pub tokens_minted: u128, // Winnings minted at distribution
+    pub players_count: u8,
+    pub timestamp: u64,
+}
+
 // ======================================
 // VIEW STRUCTURES
 // ======================================
@@ -161,6 +326,7 @@ pub struct PlayerWinning {
 pub struct PlayerView {
     #[schemars(with = "String")]
     pub account_id: AccountId,
+    pub alias: Option<String>,
     pub seat_number: u8,
     pub state: PlayerState,
     pub current_hand_index: u8,
@@ -168,6 +334,52 @@ pub struct PlayerView {
     pub total_burned_this_round: u128,
     pub time_since_last_action: u64, // seconds
     pub is_current_player: bool,
+    pub rounds_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub session_bets: Vec<u128>,
+}
+
+/// Dry-run result of distribute_winnings checks (`reason` is empty when valid)
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DistributionValidation {
+    pub valid: bool,
+    pub reason: String,
+}
+
+/// Pending signals for every seat (each signal carries its seat number)
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AllSignals {
+    pub bets: Vec<BetSignal>,
+    pub moves: Vec<MoveSignal>,
+}
+
+/// Session scoreboard for the player at a seat
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeatPlayerHistory {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    pub seat_number: u8,
+    pub joined_at: u64,
+    pub rounds_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoundFlow {
+    pub round_number: u64,
+    pub tokens_burned: u128,
+    pub tokens_minted: u128,
+    pub net_supply_change: i128, // minted - burned
 }
 
 // ======================================
@@ -185,6 +397,21 @@ pub struct GameConfig {
     pub max_bet_amount: u128,
     pub auto_start_delay_ms: u64, // Delay before auto-starting with 1 player
     pub max_players: Option<u8>, // Maximum players (3 seats)
+    pub auto_settle_single_player: bool, // Settle heads-up rounds on the player's final move
+    pub payout_rules: PayoutRules, // Allowed winnings per hand result
+    pub one_action_per_block: bool, // Limit each seat to one bet/move per block
+    pub auto_activate_observers: bool, // Observers become Active at Betting without opting in
+    pub allow_double_after_split: bool, // Permit doubling either hand after a split
+    pub strict_distribution: bool, // Distributions must settle exactly the seats that bet
+    pub max_burn_per_round: Option<u128>, // Cap on bet + double + split burns per seat and round
+    pub auto_advance_turns: bool, // Pass the turn on once the current seat finishes every hand
+    pub blackjack_payout_numerator: u128, // Natural pays bet * numerator / denominator on top of the stake
+    pub blackjack_payout_denominator: u128,
+    pub rake_basis_points: u16, // Share of each hand's profit minted to the treasury instead of the player
+    pub max_hands: u8, // Hands per seat (1 disables splitting); sizes the seat storage estimate
+    pub min_players_to_start: u8, // Players that must be ready before Betting can open (0 = no minimum)
+    pub max_pending_signals: u16, // Unprocessed bet or move signals a seat may queue before new ones are rejected
+    pub stale_round_timeout_ms: u64, // Idle time after which anyone may refund an undistributed round
 }
 
 impl Default for GameConfig {
@@ -198,6 +425,76 @@ impl Default for GameConfig {
             max_bet_amount: 1000,
             auto_start_delay_ms: 20_000, // 20 seconds
             max_players: Some(3), // Default 3 players
+            auto_settle_single_player: false,
+            payout_rules: PayoutRules::default(),
+            one_action_per_block: false,
+            auto_activate_observers: true,
+            allow_double_after_split: true,
+            strict_distribution: false,
+            max_burn_per_round: None,
+            auto_advance_turns: false,
+            blackjack_payout_numerator: 3, // 3:2
+            blackjack_payout_denominator: 2,
+            rake_basis_points: 0,
+            max_hands: MAX_HANDS_PER_SEAT as u8,
+            min_players_to_start: 0,
+            max_pending_signals: 32,
+            stale_round_timeout_ms: 600_000, // 10 minutes
+        }
+    }
+}
+
+impl GameConfig {
+    /// Expected winnings (including bet return) for a hand result; naturals use the
+    /// configured blackjack ratio, every other result the payout rules
+    pub fn expected_payout(&self, bet_amount: u128, result: HandResult) -> u128 {
+        let bps = match result {
+            HandResult::Blackjack => {
+                return bet_amount + bet_amount * self.blackjack_payout_numerator / self.blackjack_payout_denominator;
+            }
+            HandResult::Win => self.payout_rules.win_bps,
+            HandResult::Push => self.payout_rules.push_bps,
+            HandResult::Bust | HandResult::Lose => self.payout_rules.lose_bps,
+        };
+        bet_amount * bps / 10_000
+    }
+}
+
+/// Game configuration update payload (owner only, None = unchanged)
+#[derive(Serialize, Deserialize, Default, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameConfigUpdate {
+    pub betting_timeout_ms: Option<u64>,
+    pub move_timeout_ms: Option<u64>,
+    pub round_break_ms: Option<u64>,
+    pub max_inactive_time_ms: Option<u64>,
+    pub min_bet_amount: Option<u128>,
+    pub max_bet_amount: Option<u128>,
+    pub auto_start_delay_ms: Option<u64>,
+    pub max_players: Option<u8>,
+    pub allow_double_after_split: Option<bool>,
+    pub max_hands: Option<u8>,
+    pub min_players_to_start: Option<u8>,
+    pub max_pending_signals: Option<u16>,
+    pub stale_round_timeout_ms: Option<u64>,
+}
+
+/// Payout multipliers in basis points of the bet (10_000 = 1x); naturals use
+/// `GameConfig::blackjack_payout_numerator`/`denominator`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PayoutRules {
+    pub win_bps: u128,
+    pub push_bps: u128,
+    pub lose_bps: u128, // Applies to Lose and Bust
+}
+
+impl Default for PayoutRules {
+    fn default() -> Self {
+        Self {
+            win_bps: 20_000,       // 2x
+            push_bps: 10_000,      // 1x (stake returned)
+            lose_bps: 0,
         }
     }
 }
@@ -219,22 +516,47 @@ pub enum BlackjackEvent {
         seat_number: u8,
         timestamp: u64,
     },
+    SeatChanged {
+        account_id: AccountId,
+        old_seat: u8,
+        new_seat: u8,
+        timestamp: u64,
+    },
     BetPlaced {
         account_id: AccountId,
         amount: u128,
         seat_number: u8,
         timestamp: u64,
     },
+    BetCancelled {
+        account_id: AccountId,
+        amount: u128,
+        seat_number: u8,
+        timestamp: u64,
+    },
     MoveSignaled {
         account_id: AccountId,
         move_type: PlayerMove,
         timestamp: u64,
     },
+    HandBusted {
+        account_id: AccountId,
+        seat_number: u8,
+        hand_index: u8,
+        score: u8,
+        timestamp: u64,
+    },
     GameStateChanged {
         old_state: GameState,
         new_state: GameState,
         timestamp: u64,
     },
+    BettingClosed {
+        round_number: u64,
+        total_pot: u128,
+        seats_with_bets: u8,
+        timestamp: u64,
+    },
     WinningsDistributed {
         round_number: u64,
         total_minted: u128,
@@ -251,6 +573,11 @@ pub enum BlackjackEvent {
         players_refunded: u8,
         timestamp: u64,
     },
+    GameReset {
+        players_refunded: u8,
+        players_removed: u8,
+        timestamp: u64,
+    },
     GlobalPause {
         reason: String,
         timestamp: u64,
@@ -258,6 +585,34 @@ pub enum BlackjackEvent {
     GlobalResume {
         timestamp: u64,
     },
+    LowReserveAlert {
+        free_balance: u128,
+        threshold: u128,
+        timestamp: u64,
+    },
+    MaintenanceScheduled {
+        start_ns: u64,
+        end_ns: u64,
+        reason: String,
+        timestamp: u64,
+    },
+    ConfigUpdate {
+        field: String,
+        old_value: String,
+        new_value: String,
+        updated_by: AccountId,
+        timestamp: u64,
+    },
+    OwnershipProposed {
+        current_owner: AccountId,
+        proposed_owner: AccountId,
+        timestamp: u64,
+    },
+    OwnershipTransferred {
+        old_owner: AccountId,
+        new_owner: AccountId,
+        timestamp: u64,
+    },
 }
 
 // ======================================
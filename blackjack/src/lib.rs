@@ -1,9 +1,9 @@
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::{UnorderedMap, LookupMap},
-    env, log, near_bindgen, require,
+    collections::{UnorderedMap, UnorderedSet, LookupMap, Vector},
+    env, json_types::U128, log, near_bindgen, require,
     serde::{Deserialize, Serialize},
-    AccountId, NearToken, PanicOnDefault,
+    AccountId, NearToken, PanicOnDefault, Promise,
 };
 use schemars::JsonSchema;
 
@@ -21,8 +21,55 @@ pub struct GameStateView {
     pub state: GameState,
     pub round_number: u64,
     pub current_player_seat: Option<u8>,
+    pub current_move_deadline: Option<u64>, // Nanoseconds, set during seat turns
     pub available_seats: Vec<u8>,
     pub occupied_seats: Vec<u8>,
+    pub seconds_since_activity: u64,
+    pub is_stale: bool, // Inactive longer than max_inactive_time_ms
+}
+
+/// Liveness and activity metrics for monitoring
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActivityReport {
+    pub game_created_at: u64,
+    pub last_activity: u64,
+    pub seconds_since_last_activity: u64,
+    pub total_rounds: u64,
+    pub is_idle: bool, // Inactive longer than max_inactive_time_ms
+}
+
+/// Operator diagnostics snapshot
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Diagnostics {
+    pub total_supply: u128,
+    pub total_burned: u128,
+    pub occupied_seats: Vec<u8>,
+    pub game_state: GameState,
+    pub round_number: u64,
+    pub is_paused: bool, // Global pause or active maintenance window
+    pub pause_reason: Option<String>,
+    pub pending_bet_signals: u32,
+    pub pending_move_signals: u32,
+}
+
+/// Per-feature pause switches (global pause still overrides all of them)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseFlags {
+    pub claims: bool,
+    pub purchases: bool,
+    pub betting: bool,
+    pub moves: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MaintenanceWindow {
+    pub start_ns: u64,
+    pub end_ns: u64,
+    pub reason: String,
 }
 
 // Import modules
@@ -30,12 +77,14 @@ mod tokens;
 mod storage;
 mod events;
 mod game;
+mod migration;
 
 // Re-export key types for convenience
 pub use tokens::*;
 pub use storage::*;
 pub use events::*;
 pub use game::*;
+pub use migration::{AccountMigrationResult, STATE_VERSION};
 
 /// Main contract structure combining tokens and blackjack
 #[near_bindgen]
@@ -52,10 +101,16 @@ pub struct CardsContract {
     pub total_cards_purchased: u128,
     /// Total cards burned
     pub total_cards_burned: u128,
+    /// Total cards burned as transfer fees
+    pub total_transfer_fees_burned: u128,
     /// Map of account_id -> UserAccount
     pub accounts: UnorderedMap<AccountId, UserAccount>,
     /// Storage deposits by account
     pub storage_deposits: UnorderedMap<AccountId, NearToken>,
+    /// Gift log by receiver (oldest first)
+    pub gifts_received: LookupMap<AccountId, Vector<GiftRecord>>,
+    /// Seat storage reserved while an account is seated
+    pub seat_storage_reserved: LookupMap<AccountId, NearToken>,
     /// Contract settings for tokens
     pub config: ContractConfig,
     
@@ -64,30 +119,54 @@ pub struct CardsContract {
     // ========================================
     /// Fixed 3 seats (1, 2, 3) - None means empty, Some means occupied
     pub seats: LookupMap<u8, Option<SeatPlayer>>,
+    /// Reverse index of `seats` (account -> seat number)
+    pub account_to_seat: LookupMap<AccountId, u8>,
     /// Player signals pending backend processing (seat_number -> Vec<signals>)
     pub pending_bets: LookupMap<u8, Vec<BetSignal>>,
     pub pending_moves: LookupMap<u8, Vec<MoveSignal>>,
+    /// Recent client nonces of accepted moves per seat (oldest first)
+    pub move_nonces: LookupMap<u8, Vec<u64>>,
+    /// Players who left after cards were dealt, kept until their bet is settled (seat -> player)
+    pub departed_players: LookupMap<u8, SeatPlayer>,
     /// Global game state
     pub game_state: GameState,
     /// Current round number
     pub round_number: u64,
     /// Current player turn (seat number)
     pub current_player_seat: Option<u8>,
+    /// Deadline for the current seat's move (nanoseconds)
+    pub current_move_deadline: Option<u64>,
     /// Game creation time
     pub game_created_at: u64,
     pub last_activity: u64,
+    /// Last time a seat was taken or released (auto-start delay runs from here)
+    pub waiting_since: u64,
     /// Game configuration
     pub game_config: GameConfig,
     /// Statistics for blackjack
     pub blackjack_stats: BlackjackStats,
+    /// Backend-submitted result for single-player auto-settle
+    pub pending_settlement: Option<WinningsDistribution>,
+    /// Completed rounds (round_number -> summary)
+    pub round_history: LookupMap<u64, RoundSummary>,
+    /// Latest bet/double/split burns per account (capped at `MAX_BURN_HISTORY_RECORDS`)
+    pub burn_history: LookupMap<AccountId, Vec<BurnRecord>>,
+    /// Accounts watching the table without a seat
+    pub spectators: UnorderedSet<AccountId>,
     
     // ========================================
     // SHARED
     // ========================================
     /// Contract owner (admin functions)
     pub owner_id: AccountId,
+    /// Account proposed as the next owner, waiting for it to accept
+    pub pending_owner: Option<AccountId>,
     /// Admin accounts that can manage games
     pub game_admins: UnorderedMap<AccountId, bool>,
+    /// Relayer accounts allowed to claim on behalf of users
+    pub relayers: UnorderedMap<AccountId, bool>,
+    /// Account credited with the rake on winnings
+    pub treasury_id: AccountId,
     
     // ========================================
     // GLOBAL PAUSE SYSTEM
@@ -95,9 +174,21 @@ pub struct CardsContract {
     /// Global pause state for upgrades/emergencies
     pub is_globally_paused: Option<bool>,
     pub pause_reason: Option<String>,
+    /// Scheduled maintenance window (operations blocked while active)
+    pub maintenance_window: Option<MaintenanceWindow>,
+    /// Per-feature pause flags
+    pub pause_flags: PauseFlags,
+    /// Free balance below which a LowReserveAlert is emitted (None = disabled)
+    pub low_reserve_threshold: Option<NearToken>,
+    /// Set once the alert fired, cleared when the reserve recovers
+    pub low_reserve_alerted: bool,
+    /// Sequence number of the last emitted BlackjackEvent/CardEvent
+    pub event_seq: u64,
+    /// Every admin change to the token or game config, oldest first
+    pub config_audit: Vector<ConfigChange>,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct BlackjackStats {
     pub total_games_played: u64,
@@ -105,18 +196,7 @@ pub struct BlackjackStats {
     pub total_tokens_burned_betting: u128,
     pub total_winnings_distributed: u128,
     pub total_players_joined: u64,
-}
-
-impl Default for BlackjackStats {
-    fn default() -> Self {
-        Self {
-            total_games_played: 0,
-            total_hands_dealt: 0,
-            total_tokens_burned_betting: 0,
-            total_winnings_distributed: 0,
-            total_players_joined: 0,
-        }
-    }
+    pub total_rake_collected: u128,
 }
 
 #[near_bindgen]
@@ -128,6 +208,7 @@ impl CardsContract {
         
         let mut game_admins = UnorderedMap::new(b"g");
         game_admins.insert(&owner_id, &true); // Owner is automatically an admin
+        migration::write_state_version();
         
         Self {
             // Token system
@@ -135,32 +216,73 @@ impl CardsContract {
             total_cards_claimed: 0,
             total_cards_purchased: 0,
             total_cards_burned: 0,
+            total_transfer_fees_burned: 0,
             accounts: UnorderedMap::new(b"a"),
             storage_deposits: UnorderedMap::new(b"d"),
+            gifts_received: LookupMap::new(b"f"),
+            seat_storage_reserved: LookupMap::new(b"v"),
             config: ContractConfig::default(),
             
             // Blackjack system (Pure Seat-Based)
             seats: LookupMap::new(b"s"),
+            account_to_seat: LookupMap::new(b"t"),
             pending_bets: LookupMap::new(b"p"),
             pending_moves: LookupMap::new(b"m"),
+            move_nonces: LookupMap::new(b"n"),
+            departed_players: LookupMap::new(b"x"),
             game_state: GameState::WaitingForPlayers,
             round_number: 0,
             current_player_seat: None,
+            current_move_deadline: None,
             game_created_at: env::block_timestamp(),
             last_activity: env::block_timestamp(),
+            waiting_since: env::block_timestamp(),
             game_config: GameConfig::default(),
             blackjack_stats: BlackjackStats::default(),
+            pending_settlement: None,
+            round_history: LookupMap::new(b"r"),
+            burn_history: LookupMap::new(b"h"),
+            spectators: UnorderedSet::new(b"w"),
             
             // Shared
             owner_id: owner_id.clone(),
+            pending_owner: None,
             game_admins,
+            relayers: UnorderedMap::new(b"l"),
+            treasury_id: owner_id.clone(),
             
             // Global pause system
             is_globally_paused: Some(false),
             pause_reason: None,
+            maintenance_window: None,
+            pause_flags: PauseFlags::default(),
+            low_reserve_threshold: None,
+            low_reserve_alerted: false,
+            event_seq: 0,
+            config_audit: Vector::new(b"c"),
         }
     }
 
+    /// Convert state written by an older contract version (call on the contract account after deploy).
+    /// Accounts are converted afterwards with `migrate_accounts`
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        migration::migrate_state()
+    }
+
+    /// Convert up to `limit` accounts from `from_index` to the current layout (owner only).
+    /// The migration pause is lifted once the last account was converted
+    pub fn migrate_accounts(&mut self, from_index: Option<u64>, limit: u64) -> AccountMigrationResult {
+        self.assert_owner();
+        migration::migrate_accounts(self, from_index.unwrap_or(0), limit)
+    }
+
+    /// Layout version of the stored state
+    pub fn get_state_version(&self) -> u8 {
+        migration::stored_state_version()
+    }
+
     // ========================================
     // TOKEN FUNCTIONS
     // ========================================
@@ -169,12 +291,50 @@ impl CardsContract {
     #[payable]
     pub fn storage_deposit(&mut self, account_id: Option<AccountId>) -> StorageBalance {
         self.assert_not_paused();
+        self.check_low_reserve();
         tokens::storage_deposit(self, account_id)
     }
 
-    /// Withdraw unused storage deposit
-    pub fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
-        tokens::storage_withdraw(self, amount)
+    /// Withdraw unused storage deposit (to `receiver_id` when given, otherwise the caller).
+    /// Requires exactly 1 yoctoNEAR (NEP-145)
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<NearToken>, receiver_id: Option<AccountId>) -> StorageBalance {
+        self.check_low_reserve();
+        tokens::storage_withdraw(self, amount, receiver_id)
+    }
+
+    /// Restore a storage withdrawal whose transfer failed (callback)
+    #[private]
+    pub fn on_withdraw_complete(&mut self, account_id: AccountId, amount: U128) -> bool {
+        tokens::on_withdraw_complete(self, account_id, amount.0)
+    }
+
+    /// Burn cards to post a message on the configured chat contract as the caller
+    /// (the caller needs a storage deposit on the chat contract)
+    pub fn post_chat_with_burn(&mut self, message: String) -> Promise {
+        self.assert_not_paused();
+        tokens::post_chat_with_burn(self, message)
+    }
+
+    /// Restore a burn-to-chat burn whose chat post failed (callback)
+    #[private]
+    pub fn on_chat_post_complete(&mut self, account_id: AccountId, amount: U128) -> bool {
+        tokens::on_chat_post_complete(self, account_id, amount.0)
+    }
+
+    /// Close account and refund the full storage deposit (attach 1 yoctoNEAR)
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        self.assert_not_paused();
+        self.check_low_reserve();
+        tokens::storage_unregister(self, force)
+    }
+
+    /// Close idle zero-balance accounts among up to `limit` accounts from `from_index`
+    /// and refund their storage (admin only)
+    pub fn prune_inactive_accounts(&mut self, inactive_ns: u64, from_index: Option<u64>, limit: u64) -> PruneResult {
+        self.assert_admin();
+        tokens::prune_inactive_accounts(self, inactive_ns, from_index.unwrap_or(0), limit)
     }
 
     /// Get storage balance for account
@@ -182,6 +342,11 @@ impl CardsContract {
         tokens::storage_balance_of(self, account_id)
     }
 
+    /// Get the minimum storage deposit required for a specific account
+    pub fn get_storage_cost(&self, account_id: AccountId) -> NearToken {
+        storage::calculate_user_storage_cost(&account_id)
+    }
+
     /// Get storage bounds
     pub fn storage_balance_bounds(&self) -> StorageBounds {
         tokens::storage_balance_bounds(self)
@@ -190,23 +355,149 @@ impl CardsContract {
 
     /// Claim daily cards
     pub fn claim(&mut self) -> u128 {
-        self.assert_not_paused();
+        self.assert_claims_not_paused();
+        self.check_low_reserve();
         tokens::claim_daily_cards(self)
     }
 
-    /// Purchase cards with NEAR
+    /// Claim every missed interval at once, up to `max_catchup_claims`
+    pub fn claim_max(&mut self) -> u128 {
+        self.assert_claims_not_paused();
+        self.check_low_reserve();
+        tokens::claim_max(self)
+    }
+
+    /// Claim daily cards on behalf of a user (whitelisted relayers only)
+    pub fn claim_for(&mut self, account_id: AccountId) -> u128 {
+        self.assert_claims_not_paused();
+        let caller = env::predecessor_account_id();
+        require!(self.relayers.get(&caller).unwrap_or(false), "Only whitelisted relayers can claim for others");
+        tokens::claim_daily_cards_for(self, account_id)
+    }
+
+    /// Whitelist a relayer account (owner only)
+    pub fn add_relayer(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.relayers.insert(&account_id, &true);
+        log!("Relayer {} added", account_id);
+    }
+
+    /// Remove a relayer account (owner only)
+    pub fn remove_relayer(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.relayers.remove(&account_id);
+        log!("Relayer {} removed", account_id);
+    }
+
+    /// Grant game admin rights to an account (owner only)
+    pub fn add_admin(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.game_admins.insert(&account_id, &true);
+        log!("Admin {} added", account_id);
+    }
+
+    /// Revoke game admin rights; the owner always stays an admin (owner only)
+    pub fn remove_admin(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(account_id != self.owner_id, "Cannot remove the owner as admin");
+        self.game_admins.remove(&account_id);
+        log!("Admin {} removed", account_id);
+    }
+
+    /// List accounts with game admin rights (the owner always comes first)
+    pub fn get_admins(&self) -> Vec<AccountId> {
+        let mut admins = vec![self.owner_id.clone()];
+        admins.extend(
+            self.game_admins.iter()
+                .filter(|(account_id, enabled)| *enabled && *account_id != self.owner_id)
+                .map(|(account_id, _)| account_id)
+        );
+        admins
+    }
+
+    /// Get the contract owner
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    /// Propose a new owner; the transfer completes once it calls `accept_ownership` (owner only)
+    pub fn propose_new_owner(&mut self, new_owner: AccountId) {
+        self.assert_owner();
+        require!(new_owner != self.owner_id, "Account is already the owner");
+        self.pending_owner = Some(new_owner.clone());
+        
+        self.emit_event(BlackjackEvent::OwnershipProposed {
+            current_owner: self.owner_id.clone(),
+            proposed_owner: new_owner.clone(),
+            timestamp: env::block_timestamp(),
+        });
+        log!("Ownership proposed to {}", new_owner);
+    }
+
+    /// Accept a pending ownership proposal (pending owner only)
+    pub fn accept_ownership(&mut self) {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.pending_owner.as_ref() == Some(&caller),
+            "Only the pending owner can accept ownership"
+        );
+        
+        let old_owner = std::mem::replace(&mut self.owner_id, caller.clone());
+        self.pending_owner = None;
+        self.game_admins.remove(&old_owner);
+        self.game_admins.insert(&caller, &true);
+        
+        self.emit_event(BlackjackEvent::OwnershipTransferred {
+            old_owner: old_owner.clone(),
+            new_owner: caller.clone(),
+            timestamp: env::block_timestamp(),
+        });
+        log!("Ownership transferred from {} to {}", old_owner, caller);
+    }
+
+    /// Get the account proposed as the next owner, if any
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+
+    /// Purchase cards with NEAR. When `expected_cost` is given and the tier at
+    /// `tier_index` no longer costs that, the deposit is refunded and 0 returned
     #[payable]
-    pub fn purchase(&mut self, tier_index: u8) -> u128 {
+    pub fn purchase(&mut self, tier_index: u8, expected_cost: Option<NearToken>) -> u128 {
+        self.assert_purchases_not_paused();
+        self.check_low_reserve();
+        tokens::purchase_cards(self, tier_index, expected_cost)
+    }
+
+
+    /// Set or clear the display name shown at the table
+    pub fn set_alias(&mut self, alias: Option<String>) {
+        self.assert_not_paused();
+        tokens::set_alias(self, alias)
+    }
+
+    /// Record the account that referred you (once, before your first purchase)
+    pub fn register_with_referrer(&mut self, referrer: AccountId) {
         self.assert_not_paused();
-        tokens::purchase_cards(self, tier_index)
+        tokens::register_with_referrer(self, referrer)
     }
 
+    /// Gift cards to another player with a short note
+    pub fn gift_cards(&mut self, receiver_id: AccountId, amount: u128, note: String) {
+        self.assert_not_paused();
+        tokens::gift_cards(self, receiver_id, amount, note)
+    }
 
     /// Check if user can claim cards (gas-free)
     pub fn check_claim_eligibility(&self, account_id: &AccountId) -> ClaimEligibility {
         tokens::check_claim_eligibility(self, account_id)
     }
 
+    /// Check claim eligibility for up to MAX_ELIGIBILITY_BATCH accounts (gas-free)
+    pub fn check_claim_eligibility_many(&self, account_ids: Vec<AccountId>) -> Vec<AccountClaimEligibility> {
+        tokens::check_claim_eligibility_many(self, account_ids)
+    }
+
     /// Get user card balance
     pub fn get_balance(&self, account_id: &AccountId) -> u128 {
         tokens::get_balance(self, account_id)
@@ -217,11 +508,26 @@ impl CardsContract {
         tokens::get_user_stats(self, account_id)
     }
 
+    /// Get an account's leaderboard rank by total cards claimed (gas scales with account count)
+    pub fn get_user_rank(&self, account_id: AccountId) -> Option<u64> {
+        tokens::get_user_rank(self, &account_id)
+    }
+
+    /// Get gifts received by account (newest first)
+    pub fn get_gifts_received(&self, account_id: &AccountId, limit: Option<u64>) -> Vec<GiftRecord> {
+        tokens::get_gifts_received(self, account_id, limit)
+    }
+
     /// Get contract statistics
     pub fn get_contract_stats(&self) -> ContractStats {
         tokens::get_contract_stats(self)
     }
 
+    /// Get circulating supply split by claims/purchases and cards locked in bets
+    pub fn get_supply_breakdown(&self) -> SupplyBreakdown {
+        tokens::get_supply_breakdown(self)
+    }
+
     /// Get purchase tiers
     pub fn get_purchase_tiers(&self) -> &Vec<PurchaseTier> {
         tokens::get_purchase_tiers(self)
@@ -237,16 +543,72 @@ impl CardsContract {
         tokens::get_valid_burn_amounts(self)
     }
 
+    /// Get valid bet amounts
+    pub fn get_valid_bet_amounts(&self) -> &Vec<u128> {
+        tokens::get_valid_bet_amounts(self)
+    }
+
     /// Get contract configuration
     pub fn get_token_config(&self) -> &ContractConfig {
         tokens::get_config(self)
     }
 
+    /// Get admin config changes, oldest first (limit capped at 50)
+    pub fn get_config_history(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<ConfigChange> {
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(MAX_CONFIG_HISTORY_LIMIT).min(MAX_CONFIG_HISTORY_LIMIT);
+        
+        (from_index..self.config_audit.len().min(from_index.saturating_add(limit)))
+            .filter_map(|index| self.config_audit.get(index))
+            .collect()
+    }
+
     /// Update contract configuration (Owner only)
     pub fn update_token_config(&mut self, update: AdminConfigUpdate) {
         tokens::update_config(self, update)
     }
 
+    /// Add a purchase tier, kept sorted by NEAR cost (Owner only)
+    pub fn add_purchase_tier(&mut self, tier: PurchaseTier) {
+        tokens::add_purchase_tier(self, tier)
+    }
+
+    /// Remove a purchase tier by index (Owner only)
+    pub fn remove_purchase_tier(&mut self, index: u8) {
+        tokens::remove_purchase_tier(self, index)
+    }
+
+    // ========================================
+    // NEP-141 COMPATIBILITY VIEWS
+    // ========================================
+
+    /// NEP-141 balance view (maps to card balance)
+    pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(tokens::get_balance(self, &account_id))
+    }
+
+    /// NEP-141 total supply view
+    pub fn ft_total_supply(&self) -> U128 {
+        U128(self.total_supply)
+    }
+
+    /// NEP-141 transfer (attach exactly 1 yoctoNEAR)
+    #[payable]
+    pub fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        self.assert_not_paused();
+        tokens::ft_transfer(self, receiver_id, amount, memo)
+    }
+
+    /// NEP-148 token metadata
+    pub fn ft_metadata(&self) -> FungibleTokenMetadata {
+        tokens::get_ft_metadata(self)
+    }
+
+    /// Set token precision; only before any cards are minted (owner only)
+    pub fn set_decimals(&mut self, decimals: u8) {
+        tokens::set_decimals(self, decimals)
+    }
+
     // ========================================
     // BLACKJACK FUNCTIONS 
     // ========================================
@@ -257,22 +619,60 @@ impl CardsContract {
         game::player::take_seat(self, seat_number)
     }
 
+    /// Take a seat and bet in one transaction (Betting state only)
+    pub fn join_and_bet(&mut self, seat_number: u8, amount: u128) -> bool {
+        self.assert_betting_not_paused();
+        game::player::join_and_bet(self, seat_number, amount)
+    }
+
+    /// Opt in to play next round while observing
+    pub fn opt_in_next_round(&mut self) -> bool {
+        self.assert_not_paused();
+        game::player::opt_in_next_round(self)
+    }
+
+    /// Watch the table without taking a seat
+    pub fn watch_game(&mut self) -> bool {
+        self.assert_not_paused();
+        game::player::watch_game(self)
+    }
+
+    /// Stop watching the table
+    pub fn stop_watching(&mut self) -> bool {
+        game::player::stop_watching(self)
+    }
+
     /// Leave your current seat
     pub fn leave_seat(&mut self) -> bool {
         self.assert_not_paused();
         game::player::leave_seat(self)
     }
 
+    /// Move to another empty seat (WaitingForPlayers only)
+    pub fn change_seat(&mut self, new_seat: u8) -> bool {
+        self.assert_not_paused();
+        game::player::change_seat(self, new_seat)
+    }
+
     /// Place a bet (burns tokens)
     pub fn bet(&mut self, amount: u128) -> bool {
-        self.assert_not_paused();
+        self.assert_betting_not_paused();
+        self.check_low_reserve();
         game::action::place_bet(self, amount)
     }
 
-    /// Signal a move (hit, stand, double, split)
-    pub fn make_move(&mut self, move_type: PlayerMove, hand_index: u8) -> bool {
+    /// Cancel your bet while the table is still in the Betting phase
+    pub fn cancel_bet(&mut self) -> bool {
         self.assert_not_paused();
-        game::action::signal_move(self, move_type, hand_index)
+        game::action::cancel_bet(self)
+    }
+
+    /// Signal a move (hit, stand, double, split). A repeated `nonce` from the same seat
+    /// is rejected so retried transactions are not processed twice
+    pub fn make_move(&mut self, move_type: PlayerMove, hand_index: u8, nonce: Option<u64>) -> bool {
+        self.assert_moves_not_paused();
+        self.check_low_reserve();
+        game::action::signal_move(self, move_type, hand_index, nonce)
     }
 
     /// Distribute winnings (admin/backend only)
@@ -281,12 +681,188 @@ impl CardsContract {
         game::action::distribute_winnings(self, distribution)
     }
 
+    /// Check a distribution against every distribute_winnings rule without submitting it
+    pub fn validate_distribution(&self, distribution: WinningsDistribution) -> DistributionValidation {
+        match game::action::check_distribution(self, &distribution) {
+            Ok(()) => DistributionValidation { valid: true, reason: String::new() },
+            Err(reason) => DistributionValidation { valid: false, reason },
+        }
+    }
+
+    /// Submit dealer result for single-player auto-settle (admin/backend only)
+    pub fn submit_dealer_result(&mut self, distribution: WinningsDistribution) -> bool {
+        self.assert_admin();
+        game::action::submit_dealer_result(self, distribution)
+    }
+
+    /// Enable or disable single-player auto-settle (admin only)
+    pub fn set_auto_settle_single_player(&mut self, enabled: bool) {
+        self.assert_admin();
+        let old_enabled = self.game_config.auto_settle_single_player;
+        self.game_config.auto_settle_single_player = enabled;
+        game::admin::record_config_change(self, "auto_settle_single_player", old_enabled.to_string(), enabled.to_string());
+        log!("Single-player auto-settle set to {}", enabled);
+    }
+
+    /// Enable or disable the one bet/move per block limit (admin only)
+    pub fn set_one_action_per_block(&mut self, enabled: bool) {
+        self.assert_admin();
+        let old_enabled = self.game_config.one_action_per_block;
+        self.game_config.one_action_per_block = enabled;
+        game::admin::record_config_change(self, "one_action_per_block", old_enabled.to_string(), enabled.to_string());
+        log!("One action per block set to {}", enabled);
+    }
+
+    /// Enable or disable automatic activation of observers at Betting (admin only)
+    pub fn set_auto_activate_observers(&mut self, enabled: bool) {
+        self.assert_admin();
+        let old_enabled = self.game_config.auto_activate_observers;
+        self.game_config.auto_activate_observers = enabled;
+        game::admin::record_config_change(self, "auto_activate_observers", old_enabled.to_string(), enabled.to_string());
+        log!("Auto-activate observers set to {}", enabled);
+    }
+
+    /// Require distributions to settle exactly the seats with bets (admin only)
+    pub fn set_strict_distribution(&mut self, enabled: bool) {
+        self.assert_admin();
+        let old_enabled = self.game_config.strict_distribution;
+        self.game_config.strict_distribution = enabled;
+        game::admin::record_config_change(self, "strict_distribution", old_enabled.to_string(), enabled.to_string());
+        log!("Strict distribution set to {}", enabled);
+    }
+
+    /// Cap total bet + double + split burns per seat and round (admin only, None disables)
+    pub fn set_max_burn_per_round(&mut self, max_burn: Option<u128>) {
+        self.assert_admin();
+        require!(
+            max_burn.is_none_or(|max| max >= self.game_config.min_bet_amount),
+            "max_burn_per_round cannot be below min_bet_amount"
+        );
+        let old_max_burn = self.game_config.max_burn_per_round;
+        self.game_config.max_burn_per_round = max_burn;
+        game::admin::record_config_change(self, "max_burn_per_round", format!("{:?}", old_max_burn), format!("{:?}", max_burn));
+        log!("Max burn per round set to {:?}", max_burn);
+    }
+
+    /// Enable or disable passing the turn on automatically when a seat finishes (admin only)
+    pub fn set_auto_advance_turns(&mut self, enabled: bool) {
+        self.assert_admin();
+        let old_enabled = self.game_config.auto_advance_turns;
+        self.game_config.auto_advance_turns = enabled;
+        game::admin::record_config_change(self, "auto_advance_turns", old_enabled.to_string(), enabled.to_string());
+        log!("Auto-advance turns set to {}", enabled);
+    }
+
+    /// Set the bet denominations accepted at the table (admin only)
+    pub fn set_valid_bet_amounts(&mut self, amounts: Vec<u128>) {
+        self.assert_admin();
+        tokens::validate_bet_amounts(&amounts, &self.game_config);
+        log!("Valid bet amounts set to {:?}", amounts);
+        let old_amounts = std::mem::replace(&mut self.config.valid_bet_amounts, amounts);
+        tokens::record_config_change(self, "valid_bet_amounts", format!("{:?}", old_amounts), format!("{:?}", self.config.valid_bet_amounts));
+    }
+
+    /// Get blackjack game configuration
+    pub fn get_game_config(&self) -> &GameConfig {
+        &self.game_config
+    }
+
+    /// Update timeouts, bet bounds and seat limit (owner only)
+    pub fn update_game_config(&mut self, update: GameConfigUpdate) {
+        self.assert_owner();
+        game::admin::update_game_config(self, update)
+    }
+
+    /// Update payout multipliers used to validate winnings (owner only)
+    pub fn update_payout_rules(&mut self, rules: PayoutRules) {
+        self.assert_owner();
+        require!(rules.push_bps == 10_000, "Push must return exactly the stake (push_bps = 10000)");
+        let describe = |rules: &PayoutRules| format!("win={} push={} lose={}", rules.win_bps, rules.push_bps, rules.lose_bps);
+        let old_rules = describe(&self.game_config.payout_rules);
+        let new_rules = describe(&rules);
+        self.game_config.payout_rules = rules;
+        game::admin::record_config_change(self, "payout_rules", old_rules, new_rules);
+        log!("Payout rules updated by {}", env::predecessor_account_id());
+    }
+
+    /// Set the natural blackjack payout ratio, e.g. 3/2 (owner only)
+    pub fn set_blackjack_payout(&mut self, numerator: u128, denominator: u128) {
+        self.assert_owner();
+        require!(denominator > 0, "Blackjack payout denominator must be positive");
+        let old_payout = format!("{}:{}", self.game_config.blackjack_payout_numerator, self.game_config.blackjack_payout_denominator);
+        self.game_config.blackjack_payout_numerator = numerator;
+        self.game_config.blackjack_payout_denominator = denominator;
+        game::admin::record_config_change(self, "blackjack_payout", old_payout, format!("{}:{}", numerator, denominator));
+        log!("Blackjack payout set to {}:{}", numerator, denominator);
+    }
+
+    /// Set the account that receives the rake (owner only, must be registered)
+    pub fn set_treasury(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(self.accounts.get(&account_id).is_some(), "Treasury account must be registered");
+        log!("Treasury set to {}", account_id);
+        let old_treasury = std::mem::replace(&mut self.treasury_id, account_id);
+        game::admin::record_config_change(self, "treasury_id", old_treasury.to_string(), self.treasury_id.to_string());
+    }
+
+    /// Get the account that receives the rake
+    pub fn get_treasury_id(&self) -> &AccountId {
+        &self.treasury_id
+    }
+
+    /// Move cards collected by the treasury to the owner's balance (owner only)
+    pub fn withdraw_treasury_tokens(&mut self, amount: u128) {
+        tokens::withdraw_treasury_tokens(self, amount)
+    }
+
+    /// Set the rake taken from the profit of winning hands (owner only)
+    pub fn set_rake_basis_points(&mut self, rake_basis_points: u16) {
+        self.assert_owner();
+        require!(
+            rake_basis_points <= MAX_RAKE_BPS,
+            format!("Rake cannot exceed {} bps", MAX_RAKE_BPS)
+        );
+        require!(
+            rake_basis_points == 0 || self.accounts.get(&self.treasury_id).is_some(),
+            "Treasury account must be registered before enabling the rake"
+        );
+        let old_rake = self.game_config.rake_basis_points;
+        self.game_config.rake_basis_points = rake_basis_points;
+        game::admin::record_config_change(self, "rake_basis_points", old_rake.to_string(), rake_basis_points.to_string());
+        log!("Rake set to {} bps", rake_basis_points);
+    }
+
     /// Advance game state (backend trigger)
     pub fn game_mode(&mut self, new_state: GameState) -> bool {
         self.assert_admin();
         game::admin::advance_game_state(self, new_state)
     }
 
+    /// Open betting once `auto_start_delay_ms` has passed since the last seat change
+    /// with at least one active player seated (anyone may call)
+    pub fn try_auto_start(&mut self) -> bool {
+        self.assert_not_paused();
+        game::admin::try_auto_start(self)
+    }
+
+    /// Give the turn to a seat with a bet in play (admin only)
+    pub fn set_turn(&mut self, seat_number: u8) -> bool {
+        self.assert_admin();
+        game::admin::set_turn(self, seat_number)
+    }
+
+    /// Report the dealer-computed score of a hand; over 21 busts it (admin only)
+    pub fn report_hand_score(&mut self, seat_number: u8, hand_index: u8, score: u8) -> bool {
+        self.assert_admin();
+        game::admin::report_hand_score(self, seat_number, hand_index, score)
+    }
+
+    /// Close betting and emit the round's betting summary (admin only)
+    pub fn close_betting(&mut self) -> bool {
+        self.assert_admin();
+        game::admin::close_betting(self)
+    }
+
     // ========================================
     // VIEW FUNCTIONS 
     // ========================================
@@ -294,33 +870,117 @@ impl CardsContract {
     /// Get current game state and seat information
     pub fn get_game_state(&self) -> GameStateView {
         GameStateView {
-            state: self.game_state.clone(),
+            state: self.game_state,
             round_number: self.round_number,
             current_player_seat: self.current_player_seat,
+            current_move_deadline: self.current_move_deadline,
             available_seats: self.get_available_seats(),
             occupied_seats: self.get_occupied_seats(),
+            seconds_since_activity: self.inactive_ns() / 1_000_000_000,
+            is_stale: self.is_inactive(),
+        }
+    }
+
+    /// Get uptime and activity metrics in one call
+    pub fn get_activity_report(&self) -> ActivityReport {
+        ActivityReport {
+            game_created_at: self.game_created_at,
+            last_activity: self.last_activity,
+            seconds_since_last_activity: self.inactive_ns() / 1_000_000_000,
+            total_rounds: self.round_number,
+            is_idle: self.is_inactive(),
+        }
+    }
+
+    /// Seconds left for the current seat to act (0 outside seat turns)
+    pub fn seconds_remaining_for_turn(&self) -> u64 {
+        self.current_move_deadline
+            .map_or(0, |deadline| deadline.saturating_sub(env::block_timestamp()) / 1_000_000_000)
+    }
+
+    /// Contract health snapshot for operators
+    pub fn diagnostics(&self) -> Diagnostics {
+        let maintenance = self.maintenance_window.as_ref().filter(|window| {
+            let now = env::block_timestamp();
+            now >= window.start_ns && now < window.end_ns
+        });
+        let globally_paused = self.is_globally_paused.unwrap_or(false);
+        
+        Diagnostics {
+            total_supply: self.total_supply,
+            total_burned: self.total_cards_burned,
+            occupied_seats: self.get_occupied_seats(),
+            game_state: self.game_state,
+            round_number: self.round_number,
+            is_paused: globally_paused || maintenance.is_some(),
+            pause_reason: if globally_paused {
+                self.pause_reason.clone()
+            } else {
+                maintenance.map(|window| window.reason.clone())
+            },
+            pending_bet_signals: (1..=3).map(|seat| self.get_bets_signals(seat).len() as u32).sum(),
+            pending_move_signals: (1..=3).map(|seat| self.get_moves_signals(seat).len() as u32).sum(),
         }
     }
 
     /// Get player information for a specific seat
     pub fn get_seat_player(&self, seat_number: u8) -> Option<PlayerView> {
-        if seat_number < 1 || seat_number > 3 {
+        if !(1..=3).contains(&seat_number) {
             return None;
         }
         self.seats.get(&seat_number).flatten().map(|player| {
             PlayerView {
                 account_id: player.account_id.clone(),
+                alias: self.accounts.get(&player.account_id).and_then(|user| user.alias),
                 seat_number: player.seat_number,
-                state: player.state.clone(),
+                state: player.state,
                 current_hand_index: player.current_hand_index,
                 hands: player.hands.clone(),
                 total_burned_this_round: player.total_burned_this_round,
-                time_since_last_action: (env::block_timestamp() - player.last_action_time) / 1_000_000_000,
+                time_since_last_action: env::block_timestamp().saturating_sub(player.last_action_time) / 1_000_000_000,
                 is_current_player: self.current_player_seat == Some(seat_number),
+                rounds_played: player.rounds_played,
+                wins: player.wins,
+                losses: player.losses,
+                pushes: player.pushes,
+                blackjacks: player.blackjacks,
+                session_bets: player.session_bets,
             }
         })
     }
 
+    /// Get the session scoreboard for the player at a seat
+    pub fn get_seat_player_history(&self, seat_number: u8) -> Option<SeatPlayerHistory> {
+        self.seats.get(&seat_number).flatten().map(|player| SeatPlayerHistory {
+            account_id: player.account_id,
+            seat_number: player.seat_number,
+            joined_at: player.joined_at,
+            rounds_played: player.rounds_played,
+            wins: player.wins,
+            losses: player.losses,
+            pushes: player.pushes,
+            blackjacks: player.blackjacks,
+        })
+    }
+
+    /// Get several seats in one call, in request order (None for empty/invalid seats)
+    pub fn get_seat_players(&self, seats: Vec<u8>) -> Vec<Option<PlayerView>> {
+        seats.into_iter().map(|seat| self.get_seat_player(seat)).collect()
+    }
+
+    /// Whether the account may act right now (seated, current seat and in its turn state)
+    pub fn is_my_turn(&self, account_id: AccountId) -> bool {
+        let Some(seat_number) = game::player::is_player_seated(self, &account_id) else {
+            return false;
+        };
+        let turn_state = match seat_number {
+            1 => GameState::Seat1Turn,
+            2 => GameState::Seat2Turn,
+            _ => GameState::Seat3Turn,
+        };
+        self.current_player_seat == Some(seat_number) && self.game_state == turn_state
+    }
+
     /// Get all occupied seats
     pub fn get_all_players(&self) -> Vec<PlayerView> {
         (1..=3).filter_map(|seat| self.get_seat_player(seat)).collect()
@@ -336,11 +996,64 @@ impl CardsContract {
         self.pending_moves.get(&seat_number).unwrap_or_default()
     }
 
+    /// Get pending bet and move signals for all seats in one call (seat order)
+    pub fn get_all_pending_signals(&self) -> AllSignals {
+        AllSignals {
+            bets: (1..=3).flat_map(|seat| self.get_bets_signals(seat)).collect(),
+            moves: (1..=3).flat_map(|seat| self.get_moves_signals(seat)).collect(),
+        }
+    }
+
+    /// Get empty seats that still hold pending signals (indicates a cleanup bug)
+    pub fn get_orphaned_signals(&self) -> Vec<u8> {
+        (1..=3)
+            .filter(|seat| self.seats.get(seat).flatten().is_none())
+            .filter(|seat| {
+                !self.get_bets_signals(*seat).is_empty() || !self.get_moves_signals(*seat).is_empty()
+            })
+            .collect()
+    }
+
     /// Get blackjack statistics
     pub fn get_blackjack_stats(&self) -> &BlackjackStats {
         &self.blackjack_stats
     }
 
+    /// Get token flow (burned vs minted) for a completed round
+    pub fn get_round_flow(&self, round_number: u64) -> Option<RoundFlow> {
+        self.round_history.get(&round_number).map(|summary| RoundFlow {
+            round_number: summary.round_number,
+            tokens_burned: summary.tokens_burned,
+            tokens_minted: summary.tokens_minted,
+            net_supply_change: summary.tokens_minted as i128 - summary.tokens_burned as i128,
+        })
+    }
+
+    /// Get the current round's burns for a seat (bet, double, split) in order
+    pub fn get_player_burns(&self, seat_number: u8) -> Vec<BurnRecord> {
+        self.seats.get(&seat_number)
+            .flatten()
+            .map(|player| player.burns_tracking)
+            .unwrap_or_default()
+    }
+
+    /// Get an account's recent burns, oldest first (limit capped at 100)
+    pub fn get_burn_history(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<BurnRecord> {
+        let history = self.burn_history.get(&account_id).unwrap_or_default();
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(MAX_BURN_HISTORY_LIMIT).min(MAX_BURN_HISTORY_LIMIT);
+        
+        history.into_iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Number of accounts watching the table
+    pub fn get_spectator_count(&self) -> u64 {
+        self.spectators.len()
+    }
+
     /// Get available seats (1, 2, 3)
     pub fn get_available_seats(&self) -> Vec<u8> {
         (1..=3).filter(|&seat| self.seats.get(&seat).is_none()).collect()
@@ -351,17 +1064,58 @@ impl CardsContract {
         (1..=3).filter(|&seat| self.seats.get(&seat).is_some()).collect()
     }
 
-    // ========================================
-    // ADMIN FUNCTIONS
-    // ========================================
+    /// Cards burned into the current round across all seats (bets, doubles, splits)
+    pub fn get_current_pot(&self) -> u128 {
+        (1..=3)
+            .filter_map(|seat| self.seats.get(&seat).flatten())
+            .map(|player| player.total_burned_this_round)
+            .sum()
+    }
 
+    /// Number of seated players in the Active state (observers excluded)
+    pub fn get_active_player_count(&self) -> u8 {
+        game::player::count_active_players(self)
+    }
 
-    /// Kick specific player by account ID (admin only)
+    /// Check if an account holds any seat
+    pub fn is_seated(&self, account_id: AccountId) -> bool {
+        game::player::is_player_seated(self, &account_id).is_some()
+    }
+
+    /// Get the seat an account holds, if any
+    pub fn get_seat_by_account(&self, account_id: AccountId) -> Option<u8> {
+        game::player::is_player_seated(self, &account_id)
+    }
+
+    // ========================================
+    // ADMIN FUNCTIONS
+    // ========================================
+
+
+    /// Kick specific player by account ID (admin only)
     pub fn kick_player_by_account(&mut self, account_id: AccountId, reason: String) -> bool {
         self.assert_admin();
         game::admin::kick_player(self, account_id, reason)
     }
     
+    /// Refund all bets of a stuck round and reset the table (owner only)
+    pub fn emergency_refund(&mut self, reason: String) -> u8 {
+        self.assert_owner();
+        game::admin::emergency_refund(self, reason)
+    }
+    
+    /// Refund a round stuck past Betting once it has been idle for the stale timeout
+    /// (anyone, also while paused so funds never stay locked behind a pause)
+    pub fn refund_stale_round(&mut self) -> u8 {
+        game::admin::refund_stale_round(self)
+    }
+    
+    /// Refund live bets, empty every seat and restart from round 0 (owner only)
+    pub fn reset_game(&mut self, keep_totals: Option<bool>) -> u8 {
+        self.assert_owner();
+        game::admin::reset_game(self, keep_totals.unwrap_or(false))
+    }
+    
     /// Auto-clear processed signals after round completion
     /// Called by backend after each round
     pub fn cleanup_round_signals(&mut self, seat_number: u8, round_number: u64) {
@@ -380,6 +1134,20 @@ impl CardsContract {
         log!("Cleaned up signals for seat {} after round {}", seat_number, round_number);
     }
     
+    /// Clear pending signals left on empty seats (admin only)
+    pub fn clear_orphaned_signals(&mut self) -> Vec<u8> {
+        self.assert_admin();
+        
+        let orphaned = self.get_orphaned_signals();
+        for seat_number in &orphaned {
+            self.pending_bets.insert(seat_number, &Vec::new());
+            self.pending_moves.insert(seat_number, &Vec::new());
+        }
+        
+        log!("Cleared orphaned signals for seats {:?}", orphaned);
+        orphaned
+    }
+    
     /// Global pause for contract upgrades (owner only)
     pub fn global_pause(&mut self, reason: String) {
         self.assert_owner();
@@ -409,11 +1177,136 @@ impl CardsContract {
         log!("Global pause lifted - operations resumed");
     }
     
+    /// Pause or resume individual features (owner only)
+    pub fn set_pause_flags(&mut self, flags: PauseFlags) {
+        self.assert_owner();
+        log!("Pause flags updated: claims={}, purchases={}, betting={}, moves={}",
+            flags.claims, flags.purchases, flags.betting, flags.moves);
+        self.pause_flags = flags;
+    }
+    
+    /// Get per-feature pause flags
+    pub fn get_pause_flags(&self) -> &PauseFlags {
+        &self.pause_flags
+    }
+    
+    /// Schedule a maintenance window during which operations are blocked (owner only)
+    pub fn schedule_maintenance(&mut self, start_ns: u64, end_ns: u64, reason: String) {
+        self.assert_owner();
+        require!(start_ns < end_ns, "Maintenance window must end after it starts");
+        require!(end_ns > env::block_timestamp(), "Maintenance window is already over");
+        
+        self.maintenance_window = Some(MaintenanceWindow {
+            start_ns,
+            end_ns,
+            reason: reason.clone(),
+        });
+        
+        self.emit_event(BlackjackEvent::MaintenanceScheduled {
+            start_ns,
+            end_ns,
+            reason: reason.clone(),
+            timestamp: env::block_timestamp(),
+        });
+        
+        log!("Maintenance scheduled from {} to {}: {}", start_ns, end_ns, reason);
+    }
+    
+    /// Cancel a scheduled maintenance window (owner only)
+    pub fn cancel_maintenance(&mut self) {
+        self.assert_owner();
+        self.maintenance_window = None;
+        log!("Maintenance window cleared");
+    }
+    
+    /// Get the scheduled maintenance window (None once it has ended)
+    pub fn get_maintenance_window(&self) -> Option<MaintenanceWindow> {
+        self.maintenance_window.clone()
+            .filter(|window| env::block_timestamp() < window.end_ns)
+    }
+    
+    /// Set the free-balance threshold for LowReserveAlert (owner only, None disables)
+    pub fn set_low_reserve_threshold(&mut self, threshold: Option<NearToken>) {
+        self.assert_owner();
+        self.low_reserve_threshold = threshold;
+        self.low_reserve_alerted = false;
+        log!("Low reserve threshold set to {:?}", threshold);
+    }
+    
+    /// NEAR held by the contract account (includes storage deposits and staked storage)
+    pub fn get_contract_near_balance(&self) -> NearToken {
+        env::account_balance()
+    }
+    
+    /// Balance the contract must keep: storage staking for everything currently stored
+    pub fn get_min_required_balance(&self) -> NearToken {
+        env::storage_byte_cost().saturating_mul(env::storage_usage() as u128)
+    }
+    
+    /// Balance above `get_min_required_balance`
+    pub fn get_free_balance(&self) -> NearToken {
+        env::account_balance().saturating_sub(self.get_min_required_balance())
+    }
+    
+    /// Emit a single LowReserveAlert when free balance drops below the threshold
+    fn check_low_reserve(&mut self) {
+        let Some(threshold) = self.low_reserve_threshold else {
+            return;
+        };
+        
+        let free_balance = self.get_free_balance();
+        if free_balance >= threshold {
+            self.low_reserve_alerted = false;
+            return;
+        }
+        if self.low_reserve_alerted {
+            return;
+        }
+        
+        self.low_reserve_alerted = true;
+        self.emit_event(BlackjackEvent::LowReserveAlert {
+            free_balance: free_balance.as_yoctonear(),
+            threshold: threshold.as_yoctonear(),
+            timestamp: env::block_timestamp(),
+        });
+        log!("Low reserve: free balance {} below threshold {}", free_balance, threshold);
+    }
+    
     /// Check if any operation should be blocked
     pub fn assert_not_paused(&self) {
         require!(!self.is_globally_paused.unwrap_or(false), 
                 format!("Contract paused: {}", 
                        self.pause_reason.as_ref().unwrap_or(&"No reason given".to_string())));
+        
+        if let Some(window) = &self.maintenance_window {
+            let now = env::block_timestamp();
+            require!(now < window.start_ns || now >= window.end_ns,
+                    format!("Contract under maintenance: {}", window.reason));
+        }
+    }
+
+    /// Check that claims are not paused (global pause included)
+    pub fn assert_claims_not_paused(&self) {
+        self.assert_not_paused();
+        require!(!self.pause_flags.claims, "Claims are paused");
+    }
+    
+    /// Check that purchases are not paused (global pause included)
+    pub fn assert_purchases_not_paused(&self) {
+        self.assert_not_paused();
+        require!(!self.pause_flags.purchases, "Purchases are paused");
+    }
+    
+    /// Check that betting is not paused (global pause included)
+    pub fn assert_betting_not_paused(&self) {
+        self.assert_not_paused();
+        require!(!self.pause_flags.betting, "Betting is paused");
+    }
+    
+    /// Check that moves are not paused (global pause included)
+    pub fn assert_moves_not_paused(&self) {
+        self.assert_not_paused();
+        require!(!self.pause_flags.moves, "Moves are paused");
     }
 
     // ========================================
@@ -441,9 +1334,24 @@ impl CardsContract {
     }
 
 
-    /// Emit event for logging (internal only)
-    fn emit_event<T: Serialize>(&self, event: T) {
-        events::emit_event(event)
+    /// Nanoseconds since the last table activity
+    fn inactive_ns(&self) -> u64 {
+        env::block_timestamp().saturating_sub(self.last_activity)
+    }
+
+    /// Whether the table has been inactive longer than `max_inactive_time_ms`
+    fn is_inactive(&self) -> bool {
+        self.inactive_ns() > self.game_config.max_inactive_time_ms * 1_000_000
+    }
+
+    /// Emit a sequenced event for logging (internal only)
+    fn emit_event<T: Serialize>(&mut self, event: T) {
+        events::emit_sequenced(self, event)
+    }
+
+    /// Sequence number of the last emitted event (0 = none yet)
+    pub fn get_current_event_seq(&self) -> u64 {
+        self.event_seq
     }
 
     /// Get contract metadata
@@ -493,7 +1401,6 @@ mod tests {
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::{testing_env, VMContext, NearToken};
     use crate::storage::STORAGE_DEPOSIT_REQUIRED;
-    use crate::game::types::*;
 
     fn get_context(predecessor_account_id: AccountId, attached_deposit: NearToken, block_timestamp: u64) -> VMContext {
         VMContextBuilder::new()
@@ -507,7 +1414,7 @@ mod tests {
     #[test]
     fn test_blackjack_contract_flow() {
         // Similar to test_guestbook_flow
-        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), 0);
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
         testing_env!(context.clone());
         
         let mut contract = CardsContract::new(accounts(0));
@@ -555,7 +1462,7 @@ mod tests {
         let mut contract = CardsContract::new(accounts(0));
         
         // Player 1 setup
-        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), 0);
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
         testing_env!(context.clone());
         contract.storage_deposit(Some(accounts(1)));
         
@@ -602,7 +1509,7 @@ mod tests {
     #[test]
     fn test_betting_and_token_burning() {
         // Similar to test_dynamic_storage_costs but for betting amounts
-        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), 0);
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
         testing_env!(context.clone());
         
         let mut contract = CardsContract::new(accounts(0));
@@ -655,7 +1562,7 @@ mod tests {
     #[test]  
     fn test_full_game_round() {
         // Integration test of full game round: join -> bet -> win -> payout
-        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), 0);
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
         testing_env!(context.clone());
         
         let mut contract = CardsContract::new(accounts(0));
@@ -693,9 +1600,10 @@ mod tests {
                     account_id: accounts(1),
                     seat_number: 1,
                     bet_amount: 50,
-                    winnings: 100, // Won double their bet
+                    bet_returned: 50,
+                    net_profit: 50, // Won double their bet
                     result: HandResult::Win,
-                    hand_index: 0,
+                    hand_index: 1,
                 }
             ],
             timestamp: 0,
@@ -719,114 +1627,3200 @@ mod tests {
         assert_eq!(blackjack_stats.total_winnings_distributed, 100);
         assert_eq!(blackjack_stats.total_hands_dealt, 1);
         
+        // Verify round flow recorded in history
+        let flow = contract.get_round_flow(1).unwrap();
+        assert_eq!(flow.tokens_burned, 50);
+        assert_eq!(flow.tokens_minted, 100);
+        assert_eq!(flow.net_supply_change, 50);
+        
         // Verify signals were cleared (seat-based)
         assert_eq!(contract.get_bets_signals(1).len(), 0);
         assert_eq!(contract.get_moves_signals(1).len(), 0);
     }
 
     #[test]
-    fn test_admin_functions_integration() {
-        // Test admin functions work together
-        let mut context = get_context(accounts(0), NearToken::from_near(0), 0); // Owner
+    fn test_round_flow() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
         testing_env!(context.clone());
         
         let mut contract = CardsContract::new(accounts(0));
         
-        // Admin controls game state
-        let advanced = contract.game_mode(GameState::Betting);
-        assert!(advanced);
+        // Setup player
+        contract.storage_deposit(Some(accounts(1)));
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
         
-        let game_state = contract.get_game_state();
-        assert_eq!(game_state.state, GameState::Betting);
+        // Betting round
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
         
-        // Owner can pause globally
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(30);
+        
+        // Player loses the hand - nothing minted
         context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        contract.distribute_winnings(WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 30,
+                    bet_returned: 0,
+                    net_profit: 0,
+                    result: HandResult::Lose,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: 0,
+        });
+        
+        let flow = contract.get_round_flow(1).unwrap();
+        assert_eq!(flow.tokens_burned, 30);
+        assert_eq!(flow.tokens_minted, 0);
+        assert_eq!(flow.net_supply_change, -30);
+        
+        // Unknown round has no flow
+        assert!(contract.get_round_flow(2).is_none());
+    }
+
+    #[test]
+    fn test_round_flow_counts_departed_bettor() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
         testing_env!(context.clone());
-        contract.global_pause("Emergency maintenance".to_string());
-        assert!(contract.is_globally_paused.unwrap_or(false));
         
-        // Operations should be blocked
-        context.predecessor_account_id = accounts(2);
-        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            context.attached_deposit = NearToken::from_near(0);
+            testing_env!(context.clone());
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
         testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
         
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            contract.storage_deposit(None)
-        }));
-        assert!(result.is_err()); // Should panic due to global pause
+        for (account, amount) in [(accounts(1), 30), (accounts(2), 50)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(amount);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::DealerTurn);
+        
+        // Seat 2 leaves before settlement but its bet still counts as burned
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        assert!(contract.leave_seat());
         
-        // Owner resumes
         context.predecessor_account_id = accounts(0);
-        context.attached_deposit = NearToken::from_near(0);
         testing_env!(context);
-        contract.global_resume();
-        assert!(!contract.is_globally_paused.unwrap_or(true));
+        let round_number = contract.round_number;
+        assert!(contract.distribute_winnings(WinningsDistribution {
+            round_number,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 30,
+                    bet_returned: 0,
+                    net_profit: 0,
+                    result: HandResult::Lose,
+                    hand_index: 1,
+                },
+                PlayerWinning {
+                    account_id: accounts(2),
+                    seat_number: 2,
+                    bet_amount: 50,
+                    bet_returned: 50,
+                    net_profit: 50,
+                    result: HandResult::Win,
+                    hand_index: 1,
+                },
+            ],
+            timestamp: 0,
+            total_minted: 100,
+        }));
+        
+        let flow = contract.get_round_flow(round_number).unwrap();
+        assert_eq!(flow.tokens_burned, 80);
+        assert_eq!(flow.tokens_minted, 100);
+        assert_eq!(flow.net_supply_change, 20);
     }
 
     #[test]
-    fn test_purchase_tiers_and_validation() {
-        // Similar to storage cost tests but for purchase validation
-        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), 0);
+    fn test_single_player_auto_settle() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
         testing_env!(context.clone());
         
         let mut contract = CardsContract::new(accounts(0));
         
-        // Setup storage
+        // Setup single player
         contract.storage_deposit(Some(accounts(1)));
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
         
-        // Test different purchase tiers
-        let tiers = contract.get_purchase_tiers();
-        assert!(tiers.len() >= 4); // Should have at least 4 tiers
+        // Admin enables auto-settle and opens betting
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_auto_settle_single_player(true);
+        contract.game_mode(GameState::Betting);
         
-        // Test each tier
-        for (tier_index, tier) in tiers.iter().enumerate().take(2) { // Test first 2 tiers
-            context.attached_deposit = tier.near_cost;
-            testing_env!(context.clone());
-            
-            let initial_balance = contract.get_balance(&accounts(1));
-            let purchased = contract.purchase(tier_index as u8);
-            
-            assert_eq!(purchased, tier.cards_amount);
-            assert_eq!(contract.get_balance(&accounts(1)), initial_balance + tier.cards_amount);
-        }
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        let balance_before_bet = contract.get_balance(&accounts(1));
+        contract.bet(50);
         
-        // Test invalid tier
-        context.attached_deposit = NearToken::from_near(0);
+        // Backend deals and submits the dealer result ahead of the player's final move
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        contract.submit_dealer_result(WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 50,
+                    bet_returned: 50,
+                    net_profit: 50,
+                    result: HandResult::Win,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: 100,
+        });
+        
+        // Player still has an open hand - nothing settled yet
+        assert!(contract.pending_settlement.is_some());
+        assert_eq!(contract.get_game_state().state, GameState::Seat1Turn);
+        
+        // Standing finishes the last hand and settles the round
+        context.predecessor_account_id = accounts(1);
         testing_env!(context);
+        assert!(contract.make_move(PlayerMove::Stand, 1, None));
         
-        let result = std::panic::catch_unwind(|| {
-            contract.purchase(99) // Invalid tier
-        });
-        assert!(result.is_err());
+        assert!(contract.pending_settlement.is_none());
+        assert_eq!(contract.get_balance(&accounts(1)), balance_before_bet - 50 + 100);
+        assert_eq!(contract.get_game_state().state, GameState::WaitingForPlayers);
+        assert_eq!(contract.get_round_flow(1).unwrap().tokens_minted, 100);
+        assert!(contract.get_seat_player(1).unwrap().hands.is_empty());
     }
 
     #[test]
-    fn test_error_conditions() {
-        // Test various error conditions
-        let context = get_context(accounts(1), NearToken::from_near(0), 0);
-        testing_env!(context);
+    fn test_double_drops_stale_dealer_result() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
         
         let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(Some(accounts(1)));
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
         
-        // Test operations without storage deposit
-        let result = std::panic::catch_unwind(|| {
-            contract.claim()
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_auto_settle_single_player(true);
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        let balance_before_bet = contract.get_balance(&accounts(1));
+        contract.bet(50);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        contract.submit_dealer_result(WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 50,
+                    bet_returned: 50,
+                    net_profit: 50,
+                    result: HandResult::Win,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: 100,
         });
-        assert!(result.is_err()); // Should fail - no storage
         
-        // Test game operations without taking seat first
-        let bet_placed = contract.bet(50);
-        assert!(!bet_placed); // Should return false, not panic
+        // Doubling finishes the hand at 100 - the result computed for 50 must not settle it
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        assert!(contract.make_move(PlayerMove::Double, 1, None));
         
-        let joined = contract.take_seat(0); // Invalid seat
-        assert!(!joined); // Should return false, not panic
+        assert!(contract.pending_settlement.is_none());
+        assert_eq!(contract.get_game_state().state, GameState::Seat1Turn);
+        assert_eq!(contract.get_balance(&accounts(1)), balance_before_bet - 100);
+        assert_eq!(contract.get_seat_player(1).unwrap().hands[0].bet_amount, 100);
+    }
+
+    #[test]
+    fn test_dealer_result_requires_auto_settle() {
+        let context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
         
-        // Test invalid seat numbers
-        let joined = contract.take_seat(4); // Invalid seat
-        assert!(!joined);
+        let mut contract = CardsContract::new(accounts(0));
+        
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.submit_dealer_result(WinningsDistribution {
+                round_number: 1,
+                distributions: vec![],
+                timestamp: 0,
+                total_minted: 0,
+            })
+        }));
+        assert!(result.is_err()); // Auto-settle disabled by default
+        assert!(contract.pending_settlement.is_none());
     }
 
-    // Import specific test modules
-    use tokens::tests as token_tests;
-    use game::tests as blackjack_tests;
+    #[test]
+    fn test_join_and_bet() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // Setup player with tokens
+        contract.storage_deposit(Some(accounts(1)));
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        
+        // Betting already open
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        assert!(contract.join_and_bet(2, 30));
+        
+        let player_view = contract.get_seat_player(2).unwrap();
+        assert_eq!(player_view.total_burned_this_round, 30);
+        assert_eq!(contract.get_balance(&accounts(1)), 970);
+        assert_eq!(contract.get_bets_signals(2).len(), 1);
+    }
+
+    #[test]
+    fn test_join_and_bet_failure_leaves_seat_empty() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // Player has storage but no tokens
+        contract.storage_deposit(Some(accounts(1)));
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        assert!(!contract.join_and_bet(1, 50)); // Insufficient balance
+        assert!(!contract.join_and_bet(1, 42)); // Invalid amount
+        
+        assert!(contract.get_seat_player(1).is_none());
+        assert_eq!(contract.get_available_seats(), vec![1, 2, 3]);
+        assert_eq!(contract.get_blackjack_stats().total_players_joined, 0);
+    }
+
+    #[test]
+    fn test_payout_rules_validation() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // Setup player with a bet in play
+        contract.storage_deposit(Some(accounts(1)));
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(30);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let distribution = |winnings: u128| WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 30,
+                    bet_returned: winnings.min(30),
+                    net_profit: winnings.saturating_sub(30),
+                    result: HandResult::Win,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: winnings,
+        };
+        
+        // Over-payout (3x on a win) is rejected
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.distribute_winnings(distribution(90))
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), 970);
+        
+        // Correct 2x win is accepted
+        assert!(contract.distribute_winnings(distribution(60)));
+        assert_eq!(contract.get_balance(&accounts(1)), 1030);
+    }
+
+    #[test]
+    fn test_push_returns_exact_stake() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let push = |winnings: u128| WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 50,
+                    bet_returned: winnings.min(50),
+                    net_profit: winnings.saturating_sub(50),
+                    result: HandResult::Push,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: winnings,
+        };
+        
+        // Push paying more or less than the stake is rejected
+        for wrong in [49, 51] {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                contract.distribute_winnings(push(wrong))
+            }));
+            assert!(result.is_err());
+        }
+        assert_eq!(contract.get_balance(&accounts(1)), 950);
+        
+        // Push returns exactly the bet
+        assert!(contract.distribute_winnings(push(50)));
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        
+        // Payout rules cannot redefine the push
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.update_payout_rules(PayoutRules { push_bps: 5_000, ..PayoutRules::default() })
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_distribution_dry_run() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        contract.bet(30);
+        
+        let distribution = |winnings: u128| WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 30,
+                    bet_returned: winnings.min(30),
+                    net_profit: winnings.saturating_sub(30),
+                    result: HandResult::Win,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: winnings,
+        };
+        
+        let valid = contract.validate_distribution(distribution(60));
+        assert!(valid.valid);
+        assert!(valid.reason.is_empty());
+        
+        let invalid = contract.validate_distribution(distribution(90));
+        assert!(!invalid.valid);
+        assert!(invalid.reason.contains("Invalid payout"));
+        
+        // Nothing was paid out
+        assert_eq!(contract.get_balance(&accounts(1)), 970);
+        assert_eq!(contract.get_game_state().state, GameState::Betting);
+    }
+
+    #[test]
+    fn test_blackjack_payout_three_to_two() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(100);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let natural = |winnings: u128| WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 100,
+                    bet_returned: winnings.min(100),
+                    net_profit: winnings.saturating_sub(100),
+                    result: HandResult::Blackjack,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: winnings,
+        };
+        
+        // A natural paid like a regular win is rejected
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.distribute_winnings(natural(200))
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), 900);
+        
+        // 3:2 on a 100 bet mints the stake plus 150
+        assert!(contract.distribute_winnings(natural(250)));
+        assert_eq!(contract.get_balance(&accounts(1)), 1150);
+        
+        contract.set_blackjack_payout(6, 5);
+        assert_eq!(contract.get_game_config().expected_payout(100, HandResult::Blackjack), 220);
+    }
+
+    #[test]
+    fn test_player_winning_json_compat() {
+        // Legacy payloads only send the total: the stake is returned first
+        let legacy: PlayerWinning = serde_json::from_str(
+            r#"{"account_id":"bob","seat_number":1,"bet_amount":50,"winnings":125,"result":"Blackjack","hand_index":1}"#
+        ).unwrap();
+        assert_eq!((legacy.bet_returned, legacy.net_profit), (50, 75));
+        
+        let lost: PlayerWinning = serde_json::from_str(
+            r#"{"account_id":"bob","seat_number":1,"bet_amount":50,"winnings":0,"result":"Lose","hand_index":1}"#
+        ).unwrap();
+        assert_eq!((lost.bet_returned, lost.net_profit), (0, 0));
+        
+        // A total that disagrees with the split is rejected, as is a payload with neither
+        assert!(serde_json::from_str::<PlayerWinning>(
+            r#"{"account_id":"bob","seat_number":1,"bet_amount":50,"bet_returned":50,"net_profit":50,"winnings":90,"result":"Win","hand_index":1}"#
+        ).is_err());
+        assert!(serde_json::from_str::<PlayerWinning>(
+            r#"{"account_id":"bob","seat_number":1,"bet_amount":50,"result":"Win","hand_index":1}"#
+        ).is_err());
+        
+        // Views still report the total next to the split
+        let json = serde_json::to_value(&legacy).unwrap();
+        assert_eq!(json["winnings"], serde_json::json!(125));
+        assert_eq!(json["bet_returned"], serde_json::json!(50));
+        assert_eq!(json["net_profit"], serde_json::json!(75));
+    }
+
+    #[test]
+    fn test_rake_on_winnings_profit() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // The rake needs a registered treasury
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.set_rake_basis_points(200)));
+        assert!(result.is_err());
+        contract.storage_deposit(None);
+        contract.set_treasury(accounts(0));
+        contract.set_rake_basis_points(200);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.storage_deposit(None);
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(100);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let supply_before = contract.get_contract_stats().total_supply;
+        let win = |bet_returned: u128, net_profit: u128| WinningsDistribution {
+            round_number: 1,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 100,
+                    bet_returned,
+                    net_profit,
+                    result: HandResult::Win,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: 200,
+        };
+        
+        // Booking the stake as profit would rake it too
+        let validation = contract.validate_distribution(win(0, 200));
+        assert!(!validation.valid);
+        assert!(validation.reason.contains("expected bet_returned 100"));
+        assert!(contract.distribute_winnings(win(100, 100)));
+        
+        // 2% of the 100 profit goes to the treasury, the stake is untouched
+        assert_eq!(contract.get_balance(&accounts(1)), 900 + 198);
+        assert_eq!(contract.get_balance(&accounts(0)), 2);
+        assert_eq!(contract.get_contract_stats().total_supply, supply_before + 200);
+        let stats = contract.get_blackjack_stats();
+        assert_eq!(stats.total_rake_collected, 2);
+        assert_eq!(stats.total_winnings_distributed, 198);
+    }
+
+    #[test]
+    fn test_payout_rules_blackjack_multiplier() {
+        let config = GameConfig::default();
+        assert_eq!(config.expected_payout(30, HandResult::Blackjack), 75);
+        assert_eq!(config.expected_payout(30, HandResult::Win), 60);
+        assert_eq!(config.expected_payout(30, HandResult::Push), 30);
+        assert_eq!(config.expected_payout(30, HandResult::Bust), 0);
+    }
+
+    #[test]
+    fn test_max_hands_resizes_seat_storage() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.claim();
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.update_game_config(GameConfigUpdate { max_hands: Some(1), ..Default::default() });
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.take_seat(1);
+        let one_hand = contract.seat_storage_reserved.get(&accounts(1)).unwrap();
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        // A single hand cannot split
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.make_move(PlayerMove::Split, 1, None)
+        }));
+        assert!(result.is_err());
+        
+        // Raising max_hands reserves more storage once the seat actually splits
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.update_game_config(GameConfigUpdate { max_hands: Some(2), ..Default::default() });
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        assert!(contract.make_move(PlayerMove::Split, 1, None));
+        let two_hands = contract.seat_storage_reserved.get(&accounts(1)).unwrap();
+        assert!(two_hands > one_hand);
+        assert_eq!(two_hands, crate::storage::calculate_blackjack_player_storage_cost(&accounts(1), 2));
+    }
+
+    #[test]
+    fn test_seat_storage_reservation() {
+        let context = get_context(accounts(1), NearToken::from_near(1), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        let available_before = contract.storage_balance_of(&accounts(1)).unwrap().available;
+        
+        // Seating reserves player storage
+        contract.take_seat(1);
+        let reserved = contract.seat_storage_reserved.get(&accounts(1)).unwrap();
+        assert_eq!(reserved, crate::storage::calculate_blackjack_player_storage_cost(&accounts(1), 2));
+        
+        let available_seated = contract.storage_balance_of(&accounts(1)).unwrap().available;
+        assert_eq!(
+            available_seated.as_yoctonear(),
+            available_before.as_yoctonear() - reserved.as_yoctonear()
+        );
+        
+        // Leaving releases it
+        contract.leave_seat();
+        assert!(contract.seat_storage_reserved.get(&accounts(1)).is_none());
+        assert_eq!(contract.storage_balance_of(&accounts(1)).unwrap().available, available_before);
+    }
+
+    #[test]
+    fn test_emergency_refund() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // Two players with tokens and seats
+        for (player, seat) in [(accounts(1), 1), (accounts(2), 2)] {
+            context.predecessor_account_id = player;
+            context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            
+            context.attached_deposit = NearToken::from_near(0);
+            testing_env!(context.clone());
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        let supply_before = contract.total_supply;
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        for (player, amount) in [(accounts(1), 50), (accounts(2), 100)] {
+            context.predecessor_account_id = player;
+            testing_env!(context.clone());
+            contract.bet(amount);
+        }
+        
+        // Round gets stuck mid-game
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        assert_eq!(contract.emergency_refund("Backend crashed".to_string()), 2);
+        
+        // Both players refunded and stats reconciled
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        assert_eq!(contract.get_balance(&accounts(2)), 1000);
+        assert_eq!(contract.total_supply, supply_before);
+        assert_eq!(contract.total_cards_burned, 0);
+        assert_eq!(contract.blackjack_stats.total_tokens_burned_betting, 0);
+        
+        // Table reset
+        assert_eq!(contract.get_game_state().state, GameState::WaitingForPlayers);
+        assert_eq!(contract.get_game_state().current_player_seat, None);
+        assert_eq!(contract.get_seat_player(1).unwrap().total_burned_this_round, 0);
+        assert!(contract.get_bets_signals(2).is_empty());
+    }
+
+    #[test]
+    fn test_supply_cap_blocks_winnings() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        // Cap at the current supply, then open betting
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.update_token_config(AdminConfigUpdate {
+            max_total_supply: Some(1000),
+            ..Default::default()
+        });
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50); // Supply drops to 950
+        
+        // A 2x win would mint back above the cap
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.distribute_winnings(WinningsDistribution {
+                round_number: 1,
+                distributions: vec![
+                    PlayerWinning {
+                        account_id: accounts(1),
+                        seat_number: 1,
+                        bet_amount: 50,
+                        bet_returned: 50,
+                        net_profit: 50,
+                        result: HandResult::Win,
+                        hand_index: 1,
+                    }
+                ],
+                timestamp: 0,
+                total_minted: 100,
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.total_supply, 950);
+    }
+
+    #[test]
+    fn test_strict_distribution_matches_bettors() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2)), (3, accounts(3))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.set_strict_distribution(true);
+        contract.game_mode(GameState::Betting);
+        
+        // Seat 3 sits this round out
+        for account in [accounts(1), accounts(2)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(50);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let winning = |account: AccountId, seat_number: u8| PlayerWinning {
+            account_id: account,
+            seat_number,
+            bet_amount: 50,
+            bet_returned: 50,
+            net_profit: 50,
+            result: HandResult::Win,
+            hand_index: 1,
+        };
+        let round_number = contract.get_game_state().round_number;
+        let distribution = |distributions: Vec<PlayerWinning>| WinningsDistribution {
+            round_number,
+            total_minted: distributions.iter().map(|w| w.winnings()).sum(),
+            distributions,
+            timestamp: 0,
+        };
+        
+        // Missing a bettor, or paying a seat that didn't bet, is rejected
+        for distributions in [
+            vec![winning(accounts(1), 1)],
+            vec![winning(accounts(1), 1), winning(accounts(2), 2), winning(accounts(3), 3)],
+        ] {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                contract.distribute_winnings(distribution(distributions))
+            }));
+            assert!(result.is_err());
+        }
+        assert_eq!(contract.get_balance(&accounts(1)), 950);
+        
+        assert!(contract.distribute_winnings(distribution(vec![winning(accounts(1), 1), winning(accounts(2), 2)])));
+        assert_eq!(contract.get_balance(&accounts(1)), 1050);
+        assert_eq!(contract.get_balance(&accounts(2)), 1050);
+        assert_eq!(contract.get_balance(&accounts(3)), 1000);
+    }
+
+    #[test]
+    fn test_one_action_per_block() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_one_action_per_block(true);
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        context.block_index = 10;
+        testing_env!(context.clone());
+        assert!(contract.bet(50));
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        // Bet and first hit in different blocks - both accepted
+        context.predecessor_account_id = accounts(1);
+        context.block_index = 11;
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Hit, 1, None));
+        
+        // Second move in the same block - rejected
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.make_move(PlayerMove::Stand, 1, None)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_moves_signals(1).len(), 1);
+        
+        // Next block - accepted
+        context.block_index = 12;
+        testing_env!(context);
+        assert!(contract.make_move(PlayerMove::Stand, 1, None));
+        assert_eq!(contract.get_moves_signals(1).len(), 2);
+    }
+
+    #[test]
+    fn test_orphaned_signals() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(2);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        assert!(contract.get_orphaned_signals().is_empty());
+        
+        // Simulate occupant removal without signal cleanup
+        contract.seats.remove(&2);
+        assert_eq!(contract.get_orphaned_signals(), vec![2]);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        assert_eq!(contract.clear_orphaned_signals(), vec![2]);
+        assert!(contract.get_orphaned_signals().is_empty());
+        assert!(contract.get_bets_signals(2).is_empty());
+    }
+
+    #[test]
+    fn test_get_seat_player_future_last_action() {
+        let context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.take_seat(1);
+        
+        // Clock skew: last action recorded ahead of the current block
+        let mut player = contract.seats.get(&1).flatten().unwrap();
+        player.last_action_time = context.block_timestamp + MINUTE_IN_NS;
+        contract.seats.insert(&1, &Some(player));
+        
+        assert_eq!(contract.get_seat_player(1).unwrap().time_since_last_action, 0);
+        assert_eq!(contract.get_all_players()[0].time_since_last_action, 0);
+    }
+
+    fn observer_contract(auto_activate: bool) -> (CardsContract, VMContext) {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.take_seat(1);
+        
+        // Player ends up observing (joined during a live round)
+        let mut player = contract.seats.get(&1).flatten().unwrap();
+        player.state = PlayerState::Observing;
+        contract.seats.insert(&1, &Some(player));
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_auto_activate_observers(auto_activate);
+        (contract, context)
+    }
+
+    #[test]
+    fn test_change_seat() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.take_seat(3);
+        let mut player = contract.seats.get(&3).flatten().unwrap();
+        player.wins = 4;
+        contract.seats.insert(&3, &Some(player));
+        
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        contract.storage_deposit(None);
+        contract.take_seat(2);
+        
+        // Seat 2 is taken
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(!contract.change_seat(2));
+        assert_eq!(contract.get_seat_player(3).unwrap().account_id, accounts(1));
+        
+        assert!(contract.change_seat(1));
+        assert!(contract.get_seat_player(3).is_none());
+        let moved = contract.get_seat_player(1).unwrap();
+        assert_eq!(moved.account_id, accounts(1));
+        assert_eq!(moved.seat_number, 1);
+        assert_eq!(moved.wins, 4);
+        assert_eq!(contract.get_available_seats(), vec![3]);
+        
+        // Not seated at all
+        context.predecessor_account_id = accounts(3);
+        testing_env!(context);
+        assert!(!contract.change_seat(3));
+    }
+
+    #[test]
+    fn test_active_player_count_and_is_seated() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        assert_eq!(contract.get_active_player_count(), 0);
+        
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2)), (3, accounts(3))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.take_seat(seat);
+        }
+        
+        // Seat 3 observes until the next betting phase
+        let mut observer = contract.seats.get(&3).flatten().unwrap();
+        observer.state = PlayerState::Observing;
+        contract.seats.insert(&3, &Some(observer));
+        
+        assert_eq!(contract.get_active_player_count(), 2);
+        assert!(contract.is_seated(accounts(1)));
+        assert!(contract.is_seated(accounts(3)));
+        assert!(!contract.is_seated(accounts(4)));
+    }
+
+    #[test]
+    fn test_observers_auto_activated() {
+        let (mut contract, _) = observer_contract(true);
+        contract.game_mode(GameState::Betting);
+        assert_eq!(contract.get_seat_player(1).unwrap().state, PlayerState::Active);
+    }
+
+    #[test]
+    fn test_observers_require_opt_in() {
+        let (mut contract, mut context) = observer_contract(false);
+        contract.game_mode(GameState::Betting);
+        assert_eq!(contract.get_seat_player(1).unwrap().state, PlayerState::Observing);
+        
+        // Player opts in, activated at the next betting phase
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.opt_in_next_round());
+        assert_eq!(contract.get_seat_player(1).unwrap().state, PlayerState::WaitingForNextRound);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        contract.game_mode(GameState::WaitingForPlayers);
+        contract.game_mode(GameState::Betting);
+        assert_eq!(contract.get_seat_player(1).unwrap().state, PlayerState::Active);
+    }
+
+    #[test]
+    fn test_update_game_config() {
+        let context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.update_game_config(GameConfigUpdate {
+            move_timeout_ms: Some(15_000),
+            ..Default::default()
+        });
+        
+        assert_eq!(contract.get_game_config().move_timeout_ms, 15_000);
+        assert_eq!(contract.get_game_config().betting_timeout_ms, 45_000); // Untouched
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log.contains("move_timeout_ms")));
+        
+        // Inverted bet bounds are rejected and nothing changes
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.update_game_config(GameConfigUpdate {
+                min_bet_amount: Some(500),
+                max_bet_amount: Some(100),
+                move_timeout_ms: Some(1_000),
+                ..Default::default()
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_game_config().move_timeout_ms, 15_000);
+    }
+
+    #[test]
+    fn test_activity_report() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        // Bet 10 seconds later refreshes last_activity
+        let bet_time = MINUTE_IN_NS + 10_000_000_000;
+        context.predecessor_account_id = accounts(1);
+        context.block_timestamp = bet_time;
+        testing_env!(context.clone());
+        assert!(contract.bet(10));
+        
+        let report = contract.get_activity_report();
+        assert_eq!(report.game_created_at, MINUTE_IN_NS);
+        assert_eq!(report.last_activity, bet_time);
+        assert_eq!(report.seconds_since_last_activity, 0);
+        assert_eq!(report.total_rounds, contract.round_number);
+        assert!(!report.is_idle);
+        
+        // Past max_inactive_time_ms (3 minutes) the table is idle
+        context.block_timestamp = bet_time + 200_000_000_000;
+        testing_env!(context);
+        let report = contract.get_activity_report();
+        assert_eq!(report.seconds_since_last_activity, 200);
+        assert!(report.is_idle);
+    }
+
+    fn split_hand_contract(allow_double_after_split: bool) -> (CardsContract, VMContext) {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.update_game_config(GameConfigUpdate {
+            allow_double_after_split: Some(allow_double_after_split),
+            ..Default::default()
+        });
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        // Split moves play to hand 2
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Split, 1, None));
+        (contract, context)
+    }
+
+    #[test]
+    fn test_double_after_split_allowed() {
+        let (mut contract, _) = split_hand_contract(true);
+        let balance_before = contract.get_balance(&accounts(1));
+        
+        assert!(contract.make_move(PlayerMove::Double, 2, None));
+        assert_eq!(contract.get_balance(&accounts(1)), balance_before - 50);
+        assert!(contract.get_seat_player(1).unwrap().hands[1].has_doubled);
+    }
+
+    #[test]
+    fn test_double_after_split_rejected() {
+        let (mut contract, _) = split_hand_contract(false);
+        let balance_before = contract.get_balance(&accounts(1));
+        
+        assert!(!contract.make_move(PlayerMove::Double, 2, None));
+        assert_eq!(contract.get_balance(&accounts(1)), balance_before); // Nothing burned
+        assert!(!contract.get_seat_player(1).unwrap().hands[1].has_doubled);
+        
+        // Other moves on the split hand still work
+        assert!(contract.make_move(PlayerMove::Stand, 2, None));
+    }
+
+    #[test]
+    fn test_max_burn_per_round_blocks_double_after_split() {
+        let (mut contract, mut context) = split_hand_contract(true);
+        
+        // Bet + split already burned 100; doubling hand 2 would reach 150
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_max_burn_per_round(Some(120));
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        let balance_before = contract.get_balance(&accounts(1));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.make_move(PlayerMove::Double, 2, None)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), balance_before);
+        assert_eq!(contract.get_seat_player(1).unwrap().total_burned_this_round, 100);
+        
+        // Moves that burn nothing are unaffected
+        assert!(contract.make_move(PlayerMove::Stand, 2, None));
+    }
+
+    #[test]
+    fn test_max_burn_per_round_blocks_split() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_max_burn_per_round(Some(75));
+        contract.game_mode(GameState::Betting);
+        
+        // Bets above the cap are rejected outright
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.bet(100)));
+        assert!(result.is_err());
+        assert!(contract.bet(50));
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.make_move(PlayerMove::Split, 1, None)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_seat_player(1).unwrap().hands.len(), 1);
+    }
+
+    #[test]
+    fn test_seat_player_history_counts_results() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        // Round 1 wins, round 2 loses
+        for (result, winnings) in [(HandResult::Win, 100), (HandResult::Lose, 0)] {
+            context.predecessor_account_id = accounts(0);
+            testing_env!(context.clone());
+            contract.game_mode(GameState::Betting);
+            
+            context.predecessor_account_id = accounts(1);
+            testing_env!(context.clone());
+            contract.bet(50);
+            
+            context.predecessor_account_id = accounts(0);
+            testing_env!(context.clone());
+            let round_number = contract.get_game_state().round_number;
+            assert!(contract.distribute_winnings(WinningsDistribution {
+                round_number,
+                distributions: vec![PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 50,
+                    bet_returned: winnings.min(50),
+                    net_profit: winnings.saturating_sub(50),
+                    result,
+                    hand_index: 1,
+                }],
+                timestamp: 0,
+                total_minted: winnings,
+            }));
+        }
+        
+        let history = contract.get_seat_player_history(1).unwrap();
+        assert_eq!(history.account_id, accounts(1));
+        assert_eq!(history.rounds_played, 2);
+        assert_eq!((history.wins, history.losses, history.pushes, history.blackjacks), (1, 1, 0, 0));
+        
+        let view = contract.get_seat_player(1).unwrap();
+        assert_eq!((view.rounds_played, view.wins, view.losses), (2, 1, 1));
+        assert!(contract.get_seat_player_history(2).is_none());
+    }
+
+    #[test]
+    fn test_session_bets_track_settled_rounds() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        assert!(contract.get_seat_player(1).unwrap().session_bets.is_empty());
+        
+        for bet in [50, 30] {
+            context.predecessor_account_id = accounts(0);
+            testing_env!(context.clone());
+            contract.game_mode(GameState::Betting);
+            
+            context.predecessor_account_id = accounts(1);
+            testing_env!(context.clone());
+            contract.bet(bet);
+            
+            context.predecessor_account_id = accounts(0);
+            testing_env!(context.clone());
+            let round_number = contract.get_game_state().round_number;
+            assert!(contract.distribute_winnings(WinningsDistribution {
+                round_number,
+                distributions: vec![PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: bet,
+                    bet_returned: 0,
+                    net_profit: 0,
+                    result: HandResult::Lose,
+                    hand_index: 1,
+                }],
+                timestamp: 0,
+                total_minted: 0,
+            }));
+        }
+        assert_eq!(contract.get_seat_player(1).unwrap().session_bets, vec![50, 30]);
+        
+        // Cancelled and refunded bets never settle, so they are not recorded
+        contract.game_mode(GameState::Betting);
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        assert!(contract.cancel_bet());
+        contract.bet(100);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        contract.emergency_refund("stuck".to_string());
+        assert_eq!(contract.get_seat_player(1).unwrap().session_bets, vec![50, 30]);
+    }
+
+    #[test]
+    fn test_session_bets_capped() {
+        let context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.take_seat(1);
+        
+        let mut player = contract.seats.get(&1).flatten().unwrap();
+        for round in 0..(MAX_SESSION_BETS as u128 + 10) {
+            player.record_session_bet(round);
+        }
+        contract.seats.insert(&1, &Some(player));
+        
+        // Only the most recent rounds are kept, oldest first
+        let session_bets = contract.get_seat_player(1).unwrap().session_bets;
+        assert_eq!(session_bets.len(), MAX_SESSION_BETS);
+        assert_eq!(session_bets.first(), Some(&10));
+        assert_eq!(session_bets.last(), Some(&(MAX_SESSION_BETS as u128 + 9)));
+    }
+
+    #[test]
+    fn test_alias_shown_in_player_view() {
+        let context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.take_seat(1);
+        assert_eq!(contract.get_seat_player(1).unwrap().alias, None);
+        
+        let available_before = contract.storage_balance_of(&accounts(1)).unwrap().available;
+        contract.set_alias(Some("  Lucky Seven ".to_string()));
+        
+        assert_eq!(contract.get_seat_player(1).unwrap().alias, Some("Lucky Seven".to_string()));
+        let available_after = contract.storage_balance_of(&accounts(1)).unwrap().available;
+        assert_eq!(
+            available_before.as_yoctonear() - available_after.as_yoctonear(),
+            crate::storage::calculate_alias_storage_cost("Lucky Seven").as_yoctonear()
+        );
+        
+        // Clearing the alias releases its storage
+        contract.set_alias(None);
+        assert_eq!(contract.get_seat_player(1).unwrap().alias, None);
+        assert_eq!(contract.storage_balance_of(&accounts(1)).unwrap().available, available_before);
+    }
+
+    #[test]
+    fn test_alias_validation() {
+        let context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        
+        for alias in ["x".repeat(crate::tokens::MAX_ALIAS_LENGTH + 1), "TheRealOwner".to_string(), " ".to_string()] {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                contract.set_alias(Some(alias.clone()))
+            }));
+            assert!(result.is_err(), "alias {:?} should be rejected", alias);
+        }
+        assert_eq!(contract.accounts.get(&accounts(1)).unwrap().alias, None);
+    }
+
+    #[test]
+    fn test_turn_deadline() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        assert_eq!(contract.get_game_state().current_move_deadline, None);
+        assert_eq!(contract.seconds_remaining_for_turn(), 0);
+        
+        // Turn start sets the deadline from move_timeout_ms (30s)
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        assert_eq!(contract.get_game_state().current_move_deadline, Some(MINUTE_IN_NS + 30_000_000_000));
+        assert_eq!(contract.seconds_remaining_for_turn(), 30);
+        
+        context.block_timestamp = MINUTE_IN_NS + 12_000_000_000;
+        testing_env!(context.clone());
+        assert_eq!(contract.seconds_remaining_for_turn(), 18);
+        
+        // Round end clears it
+        contract.game_mode(GameState::DealerTurn);
+        assert_eq!(contract.get_game_state().current_move_deadline, None);
+        assert_eq!(contract.seconds_remaining_for_turn(), 0);
+    }
+
+    #[test]
+    fn test_burn_history_records_bet_and_double() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        assert!(contract.get_burn_history(accounts(1), None, None).is_empty());
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(30);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Double, 1, None));
+        
+        // History survives the per-round reset
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        contract.game_mode(GameState::DealerTurn);
+        contract.game_mode(GameState::WaitingForPlayers);
+        contract.game_mode(GameState::Betting);
+        assert!(contract.get_seat_player(1).unwrap().hands.is_empty());
+        
+        let history = contract.get_burn_history(accounts(1), None, None);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].burn_type, BurnType::Bet);
+        assert_eq!(history[0].amount, 30);
+        assert_eq!(history[1].burn_type, BurnType::Double);
+        assert_eq!(history[1].amount, 30);
+        
+        // Paging
+        let page = contract.get_burn_history(accounts(1), Some(1), Some(500));
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].burn_type, BurnType::Double);
+    }
+
+    #[test]
+    fn test_burn_history_keeps_latest_records() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        // Every cancelled bet still leaves a burn record behind
+        context.predecessor_account_id = accounts(1);
+        let first_timestamp = context.block_timestamp;
+        for _ in 0..(MAX_BURN_HISTORY_RECORDS + 5) {
+            context.block_timestamp += 1;
+            testing_env!(context.clone());
+            assert!(contract.bet(10));
+            assert!(contract.cancel_bet());
+        }
+        
+        let history = contract.get_burn_history(accounts(1), None, None);
+        assert_eq!(history.len(), MAX_BURN_HISTORY_RECORDS);
+        assert_eq!(history[0].timestamp, first_timestamp + 6);
+        assert_eq!(history.last().unwrap().timestamp, context.block_timestamp);
+    }
+
+    #[test]
+    fn test_seat_caps_bound_serialized_size() {
+        // Worst case: longest account id with every vector at its cap
+        let account_id: AccountId = "a".repeat(64).parse().unwrap();
+        let hand = PlayerHand {
+            hand_index: 1,
+            bet_amount: u128::MAX,
+            is_finished: true,
+            has_doubled: true,
+            has_split: true,
+            can_hit: false,
+            result: Some(HandResult::Blackjack),
+        };
+        let burn = BurnRecord { burn_type: BurnType::Double, amount: u128::MAX, hand_index: 2, timestamp: u64::MAX };
+        let player = SeatPlayer {
+            account_id,
+            seat_number: 3,
+            state: PlayerState::Active,
+            current_hand_index: 2,
+            hands: vec![hand; MAX_HANDS_PER_SEAT],
+            total_burned_this_round: u128::MAX,
+            burns_tracking: vec![burn; MAX_BURNS_PER_ROUND],
+            joined_at: u64::MAX,
+            last_action_time: u64::MAX,
+            last_action_block: Some(u64::MAX),
+            rounds_played: u32::MAX,
+            wins: u32::MAX,
+            losses: u32::MAX,
+            pushes: u32::MAX,
+            blackjacks: u32::MAX,
+            session_bets: vec![u128::MAX; MAX_SESSION_BETS],
+        };
+        assert!(borsh::to_vec(&player).unwrap().len() <= SEAT_PLAYER_MAX_BYTES);
+    }
+
+    #[test]
+    fn test_seat_caps_reject_extra_burns() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        // Bet, split, double on hand 2, double on hand 1 fills the burn cap exactly
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        assert!(contract.make_move(PlayerMove::Split, 1, None));
+        assert!(contract.make_move(PlayerMove::Double, 2, None));
+        assert!(contract.make_move(PlayerMove::Double, 1, None));
+        
+        let player = contract.seats.get(&1).flatten().unwrap();
+        assert_eq!(player.hands.len(), MAX_HANDS_PER_SEAT);
+        assert_eq!(player.burns_tracking.len(), MAX_BURNS_PER_ROUND);
+        
+        // A seat already at the cap cannot burn again
+        let mut player = player;
+        player.hands[0].is_finished = false;
+        player.hands[0].can_hit = true;
+        player.hands[0].has_doubled = false;
+        player.current_hand_index = 1;
+        contract.seats.insert(&1, &Some(player));
+        let balance_before = contract.get_balance(&accounts(1));
+        
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.make_move(PlayerMove::Double, 1, None)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), balance_before);
+    }
+
+    #[test]
+    fn test_report_hand_score_bust_blocks_hit() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Hit, 1, None));
+        
+        // 21 or under keeps the hand live
+        context.predecessor_account_id = accounts(0);
+        context.block_index += 1;
+        testing_env!(context.clone());
+        assert!(!contract.report_hand_score(1, 1, 21));
+        assert!(contract.report_hand_score(1, 1, 24));
+        
+        let hand = &contract.seats.get(&1).flatten().unwrap().hands[0];
+        assert!(hand.is_finished);
+        assert!(!hand.can_hit);
+        assert_eq!(hand.result, Some(HandResult::Bust));
+        
+        context.predecessor_account_id = accounts(1);
+        context.block_index += 1;
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.make_move(PlayerMove::Hit, 1, None)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_duplicate_move_nonce_rejected() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Hit, 1, Some(7)));
+        
+        // Retried transaction is ignored, a fresh nonce goes through
+        context.block_index += 1;
+        testing_env!(context);
+        assert!(!contract.make_move(PlayerMove::Hit, 1, Some(7)));
+        assert_eq!(contract.get_moves_signals(1).len(), 1);
+        assert!(contract.make_move(PlayerMove::Hit, 1, Some(8)));
+        assert_eq!(contract.get_moves_signals(1).len(), 2);
+    }
+
+    #[test]
+    fn test_max_pending_signals_backpressure() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.update_game_config(GameConfigUpdate {
+            max_pending_signals: Some(2),
+            ..Default::default()
+        });
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::Seat1Turn);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Hit, 1, None));
+        assert!(contract.make_move(PlayerMove::Hit, 1, None));
+        assert!(!contract.make_move(PlayerMove::Hit, 1, None));
+        assert_eq!(contract.get_moves_signals(1).len(), 2);
+        
+        // Backend catches up and the seat can signal again
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.cleanup_round_signals(1, contract.round_number);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        assert!(contract.make_move(PlayerMove::Hit, 1, None));
+        assert_eq!(contract.get_moves_signals(1).len(), 1);
+    }
+
+    #[test]
+    fn test_reset_game_refunds_and_empties_table() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            context.attached_deposit = NearToken::from_near(0);
+            testing_env!(context.clone());
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        assert_eq!(contract.get_balance(&accounts(1)), 950);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.round_number = 7;
+        contract.round_history.insert(&3, &RoundSummary {
+            round_number: 3,
+            tokens_burned: 50,
+            tokens_minted: 100,
+            players_count: 1,
+            timestamp: 0,
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.reset_game(None)));
+        assert!(result.is_err());
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        assert_eq!(contract.reset_game(None), 1);
+        
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        assert_eq!(contract.get_balance(&accounts(2)), 1000);
+        assert!(contract.get_occupied_seats().is_empty());
+        assert_eq!(contract.get_seat_by_account(accounts(1)), None);
+        assert!(contract.get_bets_signals(1).is_empty());
+        assert_eq!(contract.game_state, GameState::WaitingForPlayers);
+        assert_eq!(contract.round_number, 0);
+        assert!(contract.get_round_flow(3).is_none());
+        assert_eq!(contract.current_player_seat, None);
+        assert_eq!(contract.get_blackjack_stats().total_players_joined, 0);
+    }
+
+    #[test]
+    fn test_refund_stale_round() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::DealerTurn);
+        
+        // Anyone may call it, but only after the timeout
+        context.predecessor_account_id = accounts(3);
+        context.block_timestamp += contract.game_config.stale_round_timeout_ms * 1_000_000 - 1;
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.refund_stale_round()));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), 950);
+        
+        // A global pause does not block the refund
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.global_pause("incident".to_string());
+        
+        context.predecessor_account_id = accounts(3);
+        context.block_timestamp += 1;
+        testing_env!(context);
+        assert_eq!(contract.refund_stale_round(), 1);
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        assert_eq!(contract.game_state, GameState::WaitingForPlayers);
+        assert!(contract.get_seat_player(1).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Round is not stale yet")]
+    fn test_refund_stale_round_huge_timeout() {
+        let context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.update_game_config(GameConfigUpdate { stale_round_timeout_ms: Some(u64::MAX), ..Default::default() });
+        contract.game_mode(GameState::Betting);
+        contract.game_mode(GameState::DealingInitialCards);
+        
+        // The deadline saturates instead of overflowing
+        contract.refund_stale_round();
+    }
+
+    #[test]
+    fn test_storage_unregister_blocked_by_live_bet() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        
+        context.attached_deposit = NearToken::from_yoctonear(1);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.storage_unregister(Some(true))
+        }));
+        assert!(result.is_err());
+        assert!(contract.accounts.get(&accounts(1)).is_some());
+        assert!(contract.get_seat_player(1).is_some());
+    }
+
+    #[test]
+    fn test_get_contract_near_balance() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let contract = CardsContract::new(accounts(0));
+        assert!(contract.get_contract_near_balance() > NearToken::from_near(0));
+        
+        context.account_balance = NearToken::from_near(42);
+        testing_env!(context);
+        assert_eq!(contract.get_contract_near_balance(), NearToken::from_near(42));
+    }
+
+    #[test]
+    fn test_low_reserve_alert_fires_once() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.set_low_reserve_threshold(Some(NearToken::from_near(5)));
+        
+        let alerts = || near_sdk::test_utils::get_logs().iter()
+            .filter(|log| log.contains("LowReserveAlert"))
+            .count();
+        
+        // Healthy reserve - no alert
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.account_balance = contract.get_min_required_balance().saturating_add(NearToken::from_near(100));
+        testing_env!(context.clone());
+        contract.storage_deposit(None);
+        assert_eq!(alerts(), 0);
+        
+        // Obligations leave only 1 NEAR free - alert fires on the next operation
+        context.attached_deposit = NearToken::from_near(0);
+        context.account_balance = contract.get_min_required_balance().saturating_add(NearToken::from_near(1));
+        testing_env!(context.clone());
+        contract.claim();
+        assert_eq!(alerts(), 1);
+        assert!(contract.low_reserve_alerted);
+        
+        // Still low - no repeat alert
+        context.attached_deposit = NearToken::from_yoctonear(1);
+        testing_env!(context.clone());
+        contract.storage_withdraw(Some(NearToken::from_yoctonear(1)), None);
+        assert_eq!(alerts(), 0);
+        assert!(contract.low_reserve_alerted);
+        
+        // Recovery re-arms the alert
+        context.account_balance = contract.get_min_required_balance().saturating_add(NearToken::from_near(100));
+        testing_env!(context);
+        contract.storage_withdraw(Some(NearToken::from_yoctonear(1)), None);
+        assert!(!contract.low_reserve_alerted);
+    }
+
+    #[test]
+    fn test_get_player_burns() {
+        let (mut contract, _) = split_hand_contract(true);
+        assert!(contract.make_move(PlayerMove::Double, 2, None));
+        
+        let burns = contract.get_player_burns(1);
+        assert_eq!(burns.len(), 3);
+        assert_eq!((burns[0].burn_type, burns[0].amount, burns[0].hand_index), (BurnType::Bet, 50, 1));
+        assert_eq!((burns[1].burn_type, burns[1].amount, burns[1].hand_index), (BurnType::Split, 50, 2));
+        assert_eq!((burns[2].burn_type, burns[2].amount, burns[2].hand_index), (BurnType::Double, 50, 2));
+        assert!(burns.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+        
+        // Empty seat has no burns
+        assert!(contract.get_player_burns(2).is_empty());
+    }
+
+    #[test]
+    fn test_spectators() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        contract.storage_deposit(None);
+        
+        // Two spectators join, duplicates are ignored
+        assert!(contract.watch_game());
+        assert!(!contract.watch_game());
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.watch_game());
+        assert_eq!(contract.get_spectator_count(), 2);
+        
+        // Taking a seat ends spectating, and seated players cannot spectate
+        contract.take_seat(1);
+        assert_eq!(contract.get_spectator_count(), 1);
+        assert!(!contract.watch_game());
+        assert!(!contract.stop_watching());
+        
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context);
+        assert!(contract.stop_watching());
+        assert_eq!(contract.get_spectator_count(), 0);
+    }
+
+    #[test]
+    fn test_game_state_staleness() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.game_mode(GameState::Betting);
+        
+        let view = contract.get_game_state();
+        assert_eq!(view.seconds_since_activity, 0);
+        assert!(!view.is_stale);
+        
+        // Exactly at the 3 minute threshold the table is not yet stale
+        context.block_timestamp = MINUTE_IN_NS + 180_000_000_000;
+        testing_env!(context.clone());
+        assert!(!contract.get_game_state().is_stale);
+        
+        context.block_timestamp += 1_000_000_000;
+        testing_env!(context);
+        let view = contract.get_game_state();
+        assert_eq!(view.seconds_since_activity, 181);
+        assert!(view.is_stale);
+    }
+
+    #[test]
+    fn test_bet_amounts_separate_from_burn_amounts() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_valid_bet_amounts(vec![30, 50, 100]);
+        contract.game_mode(GameState::Betting);
+        
+        // Bet amounts outside the bet bounds are rejected
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.set_valid_bet_amounts(vec![30, 5000])
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_valid_bet_amounts(), &vec![30, 50, 100]);
+        
+        // 10 is still burnable but no longer a valid bet
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.bet(10)));
+        assert!(result.is_err());
+        assert!(contract.get_valid_burn_amounts().contains(&10));
+        tokens::burn_cards(&mut contract, 10);
+        assert!(contract.bet(30));
+    }
+
+    #[test]
+    fn test_diagnostics_mid_round() {
+        let (mut contract, mut context) = split_hand_contract(true);
+        
+        let diagnostics = contract.diagnostics();
+        assert_eq!(diagnostics.total_supply, 900); // 1000 claimed, bet + split burned
+        assert_eq!(diagnostics.total_burned, 100);
+        assert_eq!(diagnostics.occupied_seats, vec![1]);
+        assert_eq!(diagnostics.game_state, GameState::Seat1Turn);
+        assert_eq!(diagnostics.round_number, contract.round_number);
+        assert!(!diagnostics.is_paused);
+        assert_eq!(diagnostics.pending_bet_signals, 1);
+        assert_eq!(diagnostics.pending_move_signals, 1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        contract.global_pause("upgrade".to_string());
+        let diagnostics = contract.diagnostics();
+        assert!(diagnostics.is_paused);
+        assert_eq!(diagnostics.pause_reason, Some("upgrade".to_string()));
+    }
+
+    #[test]
+    fn test_distribution_must_match_burned_bet() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(10);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let win = |bet_amount: u128| PlayerWinning {
+            account_id: accounts(1),
+            seat_number: 1,
+            bet_amount,
+            bet_returned: bet_amount,
+            net_profit: bet_amount,
+            result: HandResult::Win,
+            hand_index: 1,
+        };
+        let round_number = contract.round_number;
+        let distribution = |distributions: Vec<PlayerWinning>| WinningsDistribution {
+            round_number,
+            total_minted: distributions.iter().map(|winning| winning.winnings()).sum(),
+            distributions,
+            timestamp: 0,
+        };
+        
+        // A win on 1000 when only 10 was burned
+        let inflated = contract.validate_distribution(distribution(vec![win(1000)]));
+        assert!(!inflated.valid);
+        assert!(inflated.reason.contains("must be 10"), "{}", inflated.reason);
+        
+        // The same hand cannot be paid twice
+        let duplicate = contract.validate_distribution(distribution(vec![win(10), win(10)]));
+        assert!(!duplicate.valid);
+        assert!(duplicate.reason.contains("settled twice"), "{}", duplicate.reason);
+        
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.distribute_winnings(distribution(vec![win(1000)]))
+        }));
+        assert!(result.is_err());
+        assert!(contract.validate_distribution(distribution(vec![win(10)])).valid);
+    }
+
+    #[test]
+    fn test_leave_during_settlement_not_double_paid() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::DealerTurn);
+        
+        // Player leaves while the round is being settled - no refund
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.leave_seat());
+        assert_eq!(contract.get_balance(&accounts(1)), 950);
+        
+        // Settlement pays the win exactly once
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        assert!(contract.distribute_winnings(WinningsDistribution {
+            round_number: contract.round_number,
+            distributions: vec![
+                PlayerWinning {
+                    account_id: accounts(1),
+                    seat_number: 1,
+                    bet_amount: 50,
+                    bet_returned: 50,
+                    net_profit: 50,
+                    result: HandResult::Win,
+                    hand_index: 1,
+                }
+            ],
+            timestamp: 0,
+            total_minted: 100,
+        }));
+        assert_eq!(contract.get_balance(&accounts(1)), 1050);
+    }
+
+    #[test]
+    fn test_get_seat_players_batch() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context);
+        contract.storage_deposit(None);
+        contract.take_seat(2);
+        
+        let players = contract.get_seat_players(vec![1, 4, 2, 3]);
+        assert_eq!(players.len(), 4);
+        assert_eq!(players[0].as_ref().unwrap().account_id, accounts(1));
+        assert!(players[1].is_none()); // Invalid seat
+        assert_eq!(players[2].as_ref().unwrap().account_id, accounts(2));
+        assert!(players[3].is_none()); // Empty seat
+    }
+
+    #[test]
+    fn test_refund_after_betting_stat_reset_does_not_underflow() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        for account in [accounts(1), accounts(2)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(50);
+        }
+        
+        // Betting stat already reset while bets are still riding
+        contract.blackjack_stats.total_tokens_burned_betting = 0;
+        
+        // Leave and kick both refund without panicking
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.leave_seat());
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        assert!(contract.kick_player_by_account(accounts(2), "idle".to_string()));
+        assert_eq!(contract.get_balance(&accounts(2)), 1000);
+        assert_eq!(contract.get_blackjack_stats().total_tokens_burned_betting, 0);
+    }
+
+    #[test]
+    fn test_config_history_records_changes() {
+        let context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        assert!(contract.get_config_history(None, None).is_empty());
+        
+        contract.update_token_config(AdminConfigUpdate {
+            daily_claim_amount: Some(2000),
+            ..Default::default()
+        });
+        contract.update_game_config(GameConfigUpdate {
+            max_hands: Some(1),
+            ..Default::default()
+        });
+        
+        let history = contract.get_config_history(None, None);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].field, "daily_claim_amount");
+        assert_eq!(history[0].old_value, "1000");
+        assert_eq!(history[0].new_value, "2000");
+        assert_eq!(history[0].by, accounts(0));
+        assert_eq!(history[1].field, "max_hands");
+        assert_eq!(history[1].new_value, "1");
+        
+        let page = contract.get_config_history(Some(1), Some(500));
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].field, "max_hands");
+    }
+
+    #[test]
+    fn test_admin_setters_record_config_history() {
+        let mut context = get_context(accounts(2), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        contract.set_treasury(accounts(2));
+        contract.set_rake_basis_points(500);
+        contract.set_blackjack_payout(6, 5);
+        contract.update_payout_rules(PayoutRules { win_bps: 19_000, ..PayoutRules::default() });
+        contract.set_strict_distribution(true);
+        contract.set_max_burn_per_round(Some(200));
+        contract.set_auto_advance_turns(true);
+        contract.set_valid_bet_amounts(vec![10, 50, 100]);
+        
+        let history = contract.get_config_history(None, None);
+        let fields: Vec<&str> = history.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, vec![
+            "treasury_id",
+            "rake_basis_points",
+            "blackjack_payout",
+            "payout_rules",
+            "strict_distribution",
+            "max_burn_per_round",
+            "auto_advance_turns",
+            "valid_bet_amounts",
+        ]);
+        assert_eq!(history[0].new_value, accounts(2).to_string());
+        assert_eq!(history[1].old_value, "0");
+        assert_eq!(history[1].new_value, "500");
+        assert_eq!(history[2].old_value, "3:2");
+        assert_eq!(history[2].new_value, "6:5");
+        assert_eq!(history[3].new_value, "win=19000 push=10000 lose=0");
+        assert!(history.iter().all(|change| change.by == accounts(0)));
+    }
+
+    #[test]
+    fn test_min_players_to_start() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.update_game_config(GameConfigUpdate {
+            min_players_to_start: Some(2),
+            ..Default::default()
+        });
+        
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.take_seat(seat);
+            
+            context.predecessor_account_id = accounts(0);
+            testing_env!(context.clone());
+            if seat == 1 {
+                // One player is short of the two required
+                assert!(!contract.game_mode(GameState::Betting));
+                assert_eq!(contract.game_state, GameState::WaitingForPlayers);
+            }
+        }
+        
+        assert!(contract.game_mode(GameState::Betting));
+        assert_eq!(contract.game_state, GameState::Betting);
+    }
+
+    #[test]
+    fn test_seat_by_account_index() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        assert_eq!(contract.get_seat_by_account(accounts(1)), None);
+        
+        assert!(contract.take_seat(2));
+        assert_eq!(contract.get_seat_by_account(accounts(1)), Some(2));
+        assert!(contract.change_seat(3));
+        assert_eq!(contract.get_seat_by_account(accounts(1)), Some(3));
+        assert!(contract.leave_seat());
+        assert_eq!(contract.get_seat_by_account(accounts(1)), None);
+        
+        assert!(contract.take_seat(1));
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        assert!(contract.kick_player_by_account(accounts(1), "idle".to_string()));
+        assert_eq!(contract.get_seat_by_account(accounts(1)), None);
+        assert!(!contract.is_seated(accounts(1)));
+    }
+
+    #[test]
+    fn test_game_state_transition_guard() {
+        let context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for state in [
+            GameState::Betting,
+            GameState::DealingInitialCards,
+            GameState::Seat1Turn,
+            GameState::Seat3Turn,
+            GameState::DealerTurn,
+            GameState::WaitingForPlayers,
+        ] {
+            assert!(contract.game_mode(state));
+            assert_eq!(contract.get_game_state().state, state);
+        }
+        
+        // Illegal jumps leave the state untouched
+        for illegal in [GameState::Seat1Turn, GameState::DealerTurn, GameState::WaitingForPlayers] {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.game_mode(illegal)));
+            assert!(result.is_err());
+        }
+        contract.game_mode(GameState::Betting);
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.game_mode(GameState::DealerTurn);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.game_mode(GameState::Seat2Turn)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_game_state().state, GameState::DealerTurn);
+    }
+
+    #[test]
+    fn test_try_auto_start_after_delay() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        
+        // Nobody seated yet
+        context.block_timestamp += 30_000_000_000;
+        testing_env!(context.clone());
+        assert!(!contract.try_auto_start());
+        
+        contract.take_seat(1);
+        
+        // Default delay is 20 seconds from the last seat change
+        context.block_timestamp += 19_000_000_000;
+        testing_env!(context.clone());
+        assert!(!contract.try_auto_start());
+        assert_eq!(contract.get_game_state().state, GameState::WaitingForPlayers);
+        
+        context.block_timestamp += 1_000_000_000;
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context);
+        assert!(contract.try_auto_start());
+        assert_eq!(contract.get_game_state().state, GameState::Betting);
+        
+        // Already started
+        assert!(!contract.try_auto_start());
+    }
+
+    #[test]
+    fn test_set_turn() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        // Only seat 2 bets
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        contract.bet(10);
+        
+        context.predecessor_account_id = accounts(0);
+        context.block_timestamp += 5_000_000_000;
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        for seat in [1, 3, 4] {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.set_turn(seat)));
+            assert!(result.is_err(), "seat {} has no bet", seat);
+        }
+        assert_eq!(contract.get_game_state().state, GameState::DealingInitialCards);
+        
+        assert!(contract.set_turn(2));
+        let view = contract.get_game_state();
+        assert_eq!(view.state, GameState::Seat2Turn);
+        assert_eq!(view.current_player_seat, Some(2));
+        assert_eq!(view.current_move_deadline, Some(context.block_timestamp + 30_000_000_000));
+    }
+
+    #[test]
+    fn test_close_betting_summary_event() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (3, accounts(3))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        for (account, amount) in [(accounts(1), 10), (accounts(3), 50)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            assert!(contract.bet(amount));
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        assert!(contract.close_betting());
+        assert_eq!(contract.get_game_state().state, GameState::DealingInitialCards);
+        
+        let summary = near_sdk::test_utils::get_logs().into_iter()
+            .find(|log| log.contains("BettingClosed"))
+            .expect("BettingClosed event emitted");
+        let event: serde_json::Value = serde_json::from_str(summary.trim_start_matches("EVENT_JSON:")).unwrap();
+        let closed = &event["event"]["BettingClosed"];
+        assert_eq!(closed["round_number"], contract.get_game_state().round_number);
+        assert_eq!(closed["total_pot"], 60);
+        assert_eq!(closed["seats_with_bets"], 2);
+        assert_eq!(closed["timestamp"], context.block_timestamp);
+        
+        // Betting is already closed
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.close_betting()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_auto_advance_turns() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.set_auto_advance_turns(true);
+        contract.game_mode(GameState::Betting);
+        
+        for account in [accounts(1), accounts(2)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(10);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.set_turn(1);
+        
+        // Player 1 standing hands the turn to player 2
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Stand, 1, None));
+        let view = contract.get_game_state();
+        assert_eq!(view.state, GameState::Seat2Turn);
+        assert_eq!(view.current_player_seat, Some(2));
+        assert!(view.current_move_deadline.is_some());
+        
+        // A hit keeps the turn
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        assert!(contract.make_move(PlayerMove::Hit, 1, None));
+        assert_eq!(contract.get_game_state().state, GameState::Seat2Turn);
+        
+        // Last seat finishing moves to the dealer
+        testing_env!(context);
+        assert!(contract.make_move(PlayerMove::Stand, 1, None));
+        let view = contract.get_game_state();
+        assert_eq!(view.state, GameState::DealerTurn);
+        assert_eq!(view.current_player_seat, None);
+    }
+
+    #[test]
+    fn test_is_my_turn() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        assert!(!contract.is_my_turn(accounts(1)));
+        
+        for account in [accounts(1), accounts(2)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(10);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.set_turn(1);
+        assert!(contract.is_my_turn(accounts(1)));
+        assert!(!contract.is_my_turn(accounts(2))); // Waiting seat
+        assert!(!contract.is_my_turn(accounts(3))); // Not seated
+        
+        contract.game_mode(GameState::DealerTurn);
+        assert!(!contract.is_my_turn(accounts(1)));
+    }
+
+    #[test]
+    fn test_cancel_bet_refunds_and_clears_signals() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        assert_eq!(contract.get_balance(&accounts(1)), 950);
+        
+        assert!(contract.cancel_bet());
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        assert!(contract.get_bets_signals(1).is_empty());
+        assert_eq!(contract.get_contract_stats().total_burned, 0);
+        assert_eq!(contract.get_blackjack_stats().total_tokens_burned_betting, 0);
+        let player = contract.get_seat_player(1).unwrap();
+        assert_eq!(player.total_burned_this_round, 0);
+        assert!(player.hands.is_empty());
+        
+        // Nothing left to cancel, but a fresh bet is accepted
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.cancel_bet()));
+        assert!(result.is_err());
+        assert!(contract.bet(30));
+    }
+
+    #[test]
+    #[should_panic(expected = "Betting has closed, cards are being dealt")]
+    fn test_late_bet_rejected_while_dealing() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        contract.game_mode(GameState::DealingInitialCards);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        contract.bet(50);
+    }
+
+    #[test]
+    fn test_cancel_bet_rejected_after_betting_closes() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        contract.claim();
+        contract.take_seat(1);
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.bet(50);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.close_betting();
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.cancel_bet()));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), 950);
+    }
+
+    #[test]
+    fn test_get_current_pot() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (3, accounts(3))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        assert_eq!(contract.get_current_pot(), 0);
+        
+        for (account, amount) in [(accounts(1), 30), (accounts(3), 100)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(amount);
+        }
+        assert_eq!(contract.get_current_pot(), 130);
+    }
+
+    #[test]
+    fn test_supply_breakdown_with_active_bets() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (2, accounts(2))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        
+        for (account, amount) in [(accounts(1), 50), (accounts(2), 30)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(amount);
+        }
+        
+        let breakdown = contract.get_supply_breakdown();
+        assert_eq!(breakdown.locked_in_bets, 80);
+        assert_eq!(breakdown.claimed_in_circulation, 2000 - 80);
+        assert_eq!(breakdown.purchased_in_circulation, 0);
+    }
+
+    #[test]
+    fn test_get_all_pending_signals() {
+        let mut context = get_context(accounts(0), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        for (seat, account) in [(1, accounts(1)), (3, accounts(3))] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            contract.claim();
+            contract.take_seat(seat);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::Betting);
+        assert!(contract.get_all_pending_signals().bets.is_empty());
+        
+        for (account, amount) in [(accounts(3), 30), (accounts(1), 10)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            contract.bet(amount);
+        }
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.game_mode(GameState::DealingInitialCards);
+        contract.set_turn(1);
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        contract.make_move(PlayerMove::Stand, 1, None);
+        
+        let signals = contract.get_all_pending_signals();
+        let bets: Vec<(u8, u128)> = signals.bets.iter().map(|bet| (bet.seat_number, bet.amount)).collect();
+        assert_eq!(bets, vec![(1, 10), (3, 30)]);
+        assert_eq!(signals.moves.len(), 1);
+        assert_eq!(signals.moves[0].seat_number, 1);
+    }
+
+    #[test]
+    fn test_admin_functions_integration() {
+        // Test admin functions work together
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS); // Owner
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // Admin controls game state
+        let advanced = contract.game_mode(GameState::Betting);
+        assert!(advanced);
+        
+        let game_state = contract.get_game_state();
+        assert_eq!(game_state.state, GameState::Betting);
+        
+        // Owner can pause globally
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.global_pause("Emergency maintenance".to_string());
+        assert!(contract.is_globally_paused.unwrap_or(false));
+        
+        // Operations should be blocked
+        context.predecessor_account_id = accounts(2);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.storage_deposit(None)
+        }));
+        assert!(result.is_err()); // Should panic due to global pause
+        
+        // Owner resumes
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        contract.global_resume();
+        assert!(!contract.is_globally_paused.unwrap_or(true));
+    }
+
+    #[test]
+    fn test_scheduled_maintenance_window() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        
+        // Owner schedules a window from minute 5 to minute 10
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.schedule_maintenance(5 * MINUTE_IN_NS, 10 * MINUTE_IN_NS, "Upgrade".to_string());
+        
+        // Before the window - allowed
+        context.predecessor_account_id = accounts(1);
+        context.block_timestamp = 2 * MINUTE_IN_NS;
+        testing_env!(context.clone());
+        assert_eq!(contract.claim(), 1000);
+        
+        // Inside the window - blocked without any explicit pause
+        context.block_timestamp = 6 * MINUTE_IN_NS;
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.claim()
+        }));
+        assert!(result.is_err());
+        assert!(!contract.is_globally_paused.unwrap_or(false));
+        assert!(contract.get_maintenance_window().is_some());
+        
+        // After the window - allowed again and no longer reported
+        context.block_timestamp = 10 * MINUTE_IN_NS;
+        testing_env!(context);
+        assert_eq!(contract.claim(), 1000);
+        assert!(contract.get_maintenance_window().is_none());
+    }
+
+    #[test]
+    fn test_relayer_claim_for() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        
+        // Owner whitelists accounts(2) as relayer
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.add_relayer(accounts(2));
+        
+        // Relayer claims for accounts(1)
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        assert_eq!(contract.claim_for(accounts(1)), 1000);
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        assert_eq!(contract.get_balance(&accounts(2)), 0);
+        
+        // Interval still enforced for the target account
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.claim_for(accounts(1))
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_owner() {
+        let context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(3));
+        assert_eq!(contract.get_owner(), accounts(3));
+        
+        // The owner is listed as an admin even without a game_admins entry
+        contract.game_admins.remove(&accounts(3));
+        assert_eq!(contract.get_admins(), vec![accounts(3)]);
+    }
+
+    #[test]
+    fn test_ownership_transfer_handshake() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.propose_new_owner(accounts(1));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(1)));
+        // Nothing changes until the proposed owner accepts
+        assert_eq!(contract.get_owner(), accounts(0));
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        contract.accept_ownership();
+        assert_eq!(contract.get_owner(), accounts(1));
+        assert_eq!(contract.get_pending_owner(), None);
+        assert_eq!(contract.get_admins(), vec![accounts(1)]);
+        
+        // The previous owner lost its rights
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.propose_new_owner(accounts(0))
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_accept_ownership_rejects_other_accounts() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.propose_new_owner(accounts(1));
+        
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.accept_ownership()
+        }));
+        assert!(result.is_err());
+        
+        // Neither can the owner complete its own proposal
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.accept_ownership()
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_owner(), accounts(0));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(1)));
+    }
+
+    #[test]
+    fn test_add_and_remove_admin() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.add_admin(accounts(2));
+        assert_eq!(contract.get_admins().len(), 2);
+        assert!(contract.get_admins().contains(&accounts(2)));
+        
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        assert!(contract.game_mode(GameState::Betting));
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.remove_admin(accounts(2));
+        assert_eq!(contract.get_admins(), vec![accounts(0)]);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.remove_admin(accounts(0))
+        }));
+        assert!(result.is_err());
+        
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.game_mode(GameState::WaitingForPlayers)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.game_state, GameState::Betting);
+    }
+
+    #[test]
+    fn test_unauthorized_claim_for() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        
+        context.predecessor_account_id = accounts(3);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.claim_for(accounts(1))
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), 0);
+    }
+
+    #[test]
+    fn test_pause_only_betting() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        contract.take_seat(1);
+        
+        // Owner pauses betting only
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        contract.set_pause_flags(PauseFlags {
+            betting: true,
+            ..Default::default()
+        });
+        contract.game_mode(GameState::Betting);
+        
+        // Claims still work
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        assert_eq!(contract.claim(), 1000);
+        
+        // Betting is blocked
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.bet(10)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.get_balance(&accounts(1)), 1000);
+        assert!(!contract.is_globally_paused.unwrap_or(false));
+    }
+
+    #[test]
+    fn test_get_storage_cost() {
+        let context = get_context(accounts(1), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let contract = CardsContract::new(accounts(0));
+        let long_account: AccountId = "a-much-longer-account-name.testnet".parse().unwrap();
+        
+        assert_eq!(contract.get_storage_cost(accounts(1)), calculate_user_storage_cost(&accounts(1)));
+        assert_eq!(contract.get_storage_cost(long_account.clone()), calculate_user_storage_cost(&long_account));
+        assert!(contract.get_storage_cost(long_account) > contract.get_storage_cost(accounts(1)));
+    }
+
+    #[test]
+    fn test_ft_compatibility_views() {
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        contract.storage_deposit(None);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        contract.claim();
+        
+        assert_eq!(contract.ft_balance_of(accounts(1)), U128(contract.get_balance(&accounts(1))));
+        assert_eq!(contract.ft_balance_of(accounts(2)), U128(0));
+        assert_eq!(contract.ft_total_supply(), U128(contract.total_supply));
+        
+        let metadata = contract.ft_metadata();
+        assert_eq!(metadata.spec, "ft-1.0.0");
+        assert_eq!(metadata.decimals, 0);
+    }
+
+    #[test]
+    fn test_purchase_tiers_and_validation() {
+        // Similar to storage cost tests but for purchase validation
+        let mut context = get_context(accounts(1), NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED), MINUTE_IN_NS);
+        testing_env!(context.clone());
+        
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // Setup storage
+        contract.storage_deposit(Some(accounts(1)));
+        
+        // Test different purchase tiers
+        let tiers = contract.get_purchase_tiers().clone();
+        assert!(tiers.len() >= 4); // Should have at least 4 tiers
+        
+        // Test each tier
+        for (tier_index, tier) in tiers.iter().enumerate().take(2) { // Test first 2 tiers
+            context.attached_deposit = tier.near_cost;
+            testing_env!(context.clone());
+            
+            let initial_balance = contract.get_balance(&accounts(1));
+            let purchased = contract.purchase(tier_index as u8, Some(tier.near_cost));
+            
+            assert_eq!(purchased, tier.cards_amount);
+            assert_eq!(contract.get_balance(&accounts(1)), initial_balance + tier.cards_amount);
+        }
+        
+        // Test invalid tier
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.purchase(99, None) // Invalid tier
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_error_conditions() {
+        // Test various error conditions
+        let context = get_context(accounts(1), NearToken::from_near(0), MINUTE_IN_NS);
+        testing_env!(context);
+        
+        let mut contract = CardsContract::new(accounts(0));
+        
+        // Test operations without storage deposit
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.claim()
+        }));
+        assert!(result.is_err()); // Should fail - no storage
+        
+        // Test game operations without tokens or a seat
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.bet(50)
+        }));
+        assert!(result.is_err()); // Should fail - insufficient balance
+        
+        let joined = contract.take_seat(0); // Invalid seat
+        assert!(!joined); // Should return false, not panic
+        
+        // Test invalid seat numbers
+        let joined = contract.take_seat(4); // Invalid seat
+        assert!(!joined);
+    }
 }
@@ -0,0 +1,490 @@
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::{UnorderedMap, UnorderedSet, LookupMap, Vector},
+    env, log, require,
+    serde::{Deserialize, Serialize},
+    AccountId, NearToken,
+};
+use schemars::JsonSchema;
+use crate::{
+    events::emit_sequenced,
+    BlackjackStats, CardsContract, ContractConfig, PauseFlags, PurchaseTier, UserAccount,
+    game::{BetSignal, BlackjackEvent, BurnRecord, GameConfig, GameState, MoveSignal, PlayerHand, PlayerState, SeatPlayer},
+};
+
+/// Current layout version of the contract state
+pub const STATE_VERSION: u8 = 2;
+
+/// Storage key of the layout version (deployments from before versioning have none and are version 1)
+const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+
+/// Pause reason set while accounts still use an old layout
+pub const ACCOUNT_MIGRATION_PAUSE_REASON: &str = "Account migration in progress";
+
+/// Outcome of one migrate_accounts batch
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountMigrationResult {
+    pub migrated: u64,
+    /// `from_index` for the next batch (None once every account was examined)
+    pub next_index: Option<u64>,
+}
+
+// ========================================
+// VERSION 1 LAYOUT (initial release)
+// ========================================
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct UserAccountV1 {
+    balance: u128,
+    last_claim_time: u64,
+    storage_deposited: bool,
+    total_claimed: u128,
+    total_purchased: u128,
+    total_burned: u128,
+    registered_at: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct ContractConfigV1 {
+    daily_claim_amount: u128,
+    claim_interval: u64,
+    purchase_rates: Vec<PurchaseTier>,
+    valid_burn_amounts: Vec<u128>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct GameConfigV1 {
+    betting_timeout_ms: u64,
+    move_timeout_ms: u64,
+    round_break_ms: u64,
+    max_inactive_time_ms: u64,
+    min_bet_amount: u128,
+    max_bet_amount: u128,
+    auto_start_delay_ms: u64,
+    max_players: Option<u8>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct BlackjackStatsV1 {
+    total_games_played: u64,
+    total_hands_dealt: u64,
+    total_tokens_burned_betting: u128,
+    total_winnings_distributed: u128,
+    total_players_joined: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct SeatPlayerV1 {
+    account_id: AccountId,
+    seat_number: u8,
+    state: PlayerState,
+    current_hand_index: u8,
+    hands: Vec<PlayerHand>,
+    total_burned_this_round: u128,
+    burns_tracking: Vec<BurnRecord>,
+    joined_at: u64,
+    last_action_time: u64,
+    rounds_played: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct CardsContractV1 {
+    total_supply: u128,
+    total_cards_claimed: u128,
+    total_cards_purchased: u128,
+    total_cards_burned: u128,
+    accounts: UnorderedMap<AccountId, UserAccountV1>,
+    storage_deposits: UnorderedMap<AccountId, NearToken>,
+    config: ContractConfigV1,
+    seats: LookupMap<u8, Option<SeatPlayerV1>>,
+    pending_bets: LookupMap<u8, Vec<BetSignal>>,
+    pending_moves: LookupMap<u8, Vec<MoveSignal>>,
+    game_state: GameState,
+    round_number: u64,
+    current_player_seat: Option<u8>,
+    game_created_at: u64,
+    last_activity: u64,
+    game_config: GameConfigV1,
+    blackjack_stats: BlackjackStatsV1,
+    owner_id: AccountId,
+    game_admins: UnorderedMap<AccountId, bool>,
+    is_globally_paused: Option<bool>,
+    pause_reason: Option<String>,
+}
+
+impl From<UserAccountV1> for UserAccount {
+    fn from(old: UserAccountV1) -> Self {
+        Self {
+            balance: old.balance,
+            last_claim_time: old.last_claim_time,
+            storage_deposited: old.storage_deposited,
+            total_claimed: old.total_claimed,
+            total_purchased: old.total_purchased,
+            total_burned: old.total_burned,
+            registered_at: old.registered_at,
+            alias: None,
+            referrer: None,
+        }
+    }
+}
+
+impl From<ContractConfigV1> for ContractConfig {
+    fn from(old: ContractConfigV1) -> Self {
+        Self {
+            daily_claim_amount: old.daily_claim_amount,
+            claim_interval: old.claim_interval,
+            vip_claim_interval: old.claim_interval,
+            purchase_rates: old.purchase_rates,
+            // Bets were validated against the burn amounts before they were split
+            valid_bet_amounts: old.valid_burn_amounts.clone(),
+            valid_burn_amounts: old.valid_burn_amounts,
+            ..ContractConfig::default()
+        }
+    }
+}
+
+impl From<GameConfigV1> for GameConfig {
+    fn from(old: GameConfigV1) -> Self {
+        Self {
+            betting_timeout_ms: old.betting_timeout_ms,
+            move_timeout_ms: old.move_timeout_ms,
+            round_break_ms: old.round_break_ms,
+            max_inactive_time_ms: old.max_inactive_time_ms,
+            min_bet_amount: old.min_bet_amount,
+            max_bet_amount: old.max_bet_amount,
+            auto_start_delay_ms: old.auto_start_delay_ms,
+            max_players: old.max_players,
+            ..GameConfig::default()
+        }
+    }
+}
+
+impl From<BlackjackStatsV1> for BlackjackStats {
+    fn from(old: BlackjackStatsV1) -> Self {
+        Self {
+            total_games_played: old.total_games_played,
+            total_hands_dealt: old.total_hands_dealt,
+            total_tokens_burned_betting: old.total_tokens_burned_betting,
+            total_winnings_distributed: old.total_winnings_distributed,
+            total_players_joined: old.total_players_joined,
+            total_rake_collected: 0,
+        }
+    }
+}
+
+impl From<SeatPlayerV1> for SeatPlayer {
+    fn from(old: SeatPlayerV1) -> Self {
+        Self {
+            account_id: old.account_id,
+            seat_number: old.seat_number,
+            state: old.state,
+            current_hand_index: old.current_hand_index,
+            hands: old.hands,
+            total_burned_this_round: old.total_burned_this_round,
+            burns_tracking: old.burns_tracking,
+            joined_at: old.joined_at,
+            last_action_time: old.last_action_time,
+            last_action_block: None,
+            rounds_played: old.rounds_played,
+            wins: 0,
+            losses: 0,
+            pushes: 0,
+            blackjacks: 0,
+            session_bets: Vec::new(),
+        }
+    }
+}
+
+// ========================================
+// MIGRATION
+// ========================================
+
+/// Layout version of the stored state
+pub fn stored_state_version() -> u8 {
+    env::storage_read(STATE_VERSION_KEY).map_or(1, |version| version[0])
+}
+
+/// Record that the state uses the current layout
+pub fn write_state_version() {
+    env::storage_write(STATE_VERSION_KEY, &[STATE_VERSION]);
+}
+
+/// Convert the stored state to the current layout. Accounts are converted separately by
+/// `migrate_accounts`; the contract stays paused until every account was converted
+pub fn migrate_state() -> CardsContract {
+    let version = stored_state_version();
+    require!(version < STATE_VERSION, "State is already up to date");
+
+    let contract = match version {
+        1 => migrate_from_v1(),
+        _ => env::panic_str("Unknown state version"),
+    };
+
+    write_state_version();
+    log!("State migrated from version {} to {}", version, STATE_VERSION);
+    contract
+}
+
+fn migrate_from_v1() -> CardsContract {
+    let old: CardsContractV1 = env::state_read().unwrap_or_else(|| env::panic_str("No state to migrate"));
+    let config: ContractConfig = old.config.into();
+    let game_config: GameConfig = old.game_config.into();
+
+    // Rewrite occupied seats in place and rebuild the indexes kept alongside them
+    let mut old_seats = old.seats;
+    let mut seats: LookupMap<u8, Option<SeatPlayer>> = LookupMap::new(b"s");
+    let mut account_to_seat = LookupMap::new(b"t");
+    let mut seat_storage_reserved = LookupMap::new(b"v");
+    for seat_number in 1..=3u8 {
+        // Removed first: replacing the entry would read it back with the new layout
+        let Some(Some(player)) = old_seats.remove(&seat_number) else {
+            continue;
+        };
+        let player: SeatPlayer = player.into();
+        account_to_seat.insert(&player.account_id, &seat_number);
+        seat_storage_reserved.insert(
+            &player.account_id,
+            &crate::storage::calculate_blackjack_player_storage_cost(&player.account_id, game_config.max_hands),
+        );
+        seats.insert(&seat_number, &Some(player));
+    }
+
+    // The map's header (prefix and length) does not depend on the value type; entries are
+    // rewritten in place by `migrate_accounts`
+    let accounts: UnorderedMap<AccountId, UserAccount> = borsh::from_slice(
+        &borsh::to_vec(&old.accounts).expect("Failed to serialize accounts"),
+    ).expect("Failed to read accounts");
+
+    // Old accounts cannot be read until converted, so hold everything until they are
+    let (is_globally_paused, pause_reason) = if !accounts.is_empty() {
+        (Some(true), Some(ACCOUNT_MIGRATION_PAUSE_REASON.to_string()))
+    } else {
+        (old.is_globally_paused, old.pause_reason)
+    };
+
+    CardsContract {
+        total_supply: old.total_supply,
+        total_cards_claimed: old.total_cards_claimed,
+        total_cards_purchased: old.total_cards_purchased,
+        total_cards_burned: old.total_cards_burned,
+        total_transfer_fees_burned: 0,
+        accounts,
+        storage_deposits: old.storage_deposits,
+        gifts_received: LookupMap::new(b"f"),
+        seat_storage_reserved,
+        config,
+
+        seats,
+        account_to_seat,
+        pending_bets: old.pending_bets,
+        pending_moves: old.pending_moves,
+        move_nonces: LookupMap::new(b"n"),
+        departed_players: LookupMap::new(b"x"),
+        game_state: old.game_state,
+        round_number: old.round_number,
+        current_player_seat: old.current_player_seat,
+        current_move_deadline: None,
+        game_created_at: old.game_created_at,
+        last_activity: old.last_activity,
+        waiting_since: old.last_activity,
+        game_config,
+        blackjack_stats: old.blackjack_stats.into(),
+        pending_settlement: None,
+        round_history: LookupMap::new(b"r"),
+        burn_history: LookupMap::new(b"h"),
+        spectators: UnorderedSet::new(b"w"),
+
+        owner_id: old.owner_id.clone(),
+        pending_owner: None,
+        game_admins: old.game_admins,
+        relayers: UnorderedMap::new(b"l"),
+        treasury_id: old.owner_id,
+
+        is_globally_paused,
+        pause_reason,
+        maintenance_window: None,
+        pause_flags: PauseFlags::default(),
+        low_reserve_threshold: None,
+        low_reserve_alerted: false,
+        event_seq: 0,
+        config_audit: Vector::new(b"c"),
+    }
+}
+
+/// Convert up to `limit` accounts starting at `from_index` to the current layout. Accounts
+/// already converted are skipped, so batches may be repeated. Call again with the returned
+/// `next_index` until it is None; the last batch lifts the migration pause
+pub fn migrate_accounts(contract: &mut CardsContract, from_index: u64, limit: u64) -> AccountMigrationResult {
+    let limit = limit.min(crate::tokens::MAX_PRUNE_BATCH);
+    let end = from_index.saturating_add(limit).min(contract.accounts.len());
+
+    let mut migrated = 0u64;
+    for index in from_index..end {
+        let key_raw = contract.accounts.keys_as_vector().get_raw(index).expect("Account key missing");
+        let value_raw = contract.accounts.values_as_vector().get_raw(index).expect("Account listed without data");
+
+        // The layouts differ in length, so a current account never parses as an old one
+        let Ok(old) = UserAccountV1::try_from_slice(&value_raw) else {
+            continue;
+        };
+        let user: UserAccount = old.into();
+        contract.accounts.insert_raw(&key_raw, &borsh::to_vec(&user).expect("Failed to serialize account"));
+        migrated += 1;
+    }
+
+    let next_index = (end < contract.accounts.len()).then_some(end);
+    if next_index.is_none() && contract.pause_reason.as_deref() == Some(ACCOUNT_MIGRATION_PAUSE_REASON) {
+        contract.is_globally_paused = Some(false);
+        contract.pause_reason = None;
+        emit_sequenced(contract, BlackjackEvent::GlobalResume {
+            timestamp: env::block_timestamp(),
+        });
+    }
+
+    log!("Migrated {} accounts", migrated);
+    AccountMigrationResult { migrated, next_index }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn write_v1_state() {
+        let mut old_accounts = UnorderedMap::new(b"a");
+        for account_id in [accounts(1), accounts(2)] {
+            old_accounts.insert(&account_id, &UserAccountV1 {
+                balance: 500,
+                last_claim_time: 0,
+                storage_deposited: true,
+                total_claimed: 500,
+                total_purchased: 0,
+                total_burned: 0,
+                registered_at: 1,
+            });
+        }
+
+        let mut old_seats = LookupMap::new(b"s");
+        old_seats.insert(&1u8, &Some(SeatPlayerV1 {
+            account_id: accounts(1),
+            seat_number: 1,
+            state: PlayerState::Active,
+            current_hand_index: 1,
+            hands: Vec::new(),
+            total_burned_this_round: 0,
+            burns_tracking: Vec::new(),
+            joined_at: 1,
+            last_action_time: 1,
+            rounds_played: 4,
+        }));
+
+        let mut game_admins = UnorderedMap::new(b"g");
+        game_admins.insert(&accounts(0), &true);
+
+        env::state_write(&CardsContractV1 {
+            total_supply: 1000,
+            total_cards_claimed: 1000,
+            total_cards_purchased: 0,
+            total_cards_burned: 0,
+            accounts: old_accounts,
+            storage_deposits: UnorderedMap::new(b"d"),
+            config: ContractConfigV1 {
+                daily_claim_amount: 2000,
+                claim_interval: 2 * crate::tokens::MINUTE_IN_NS,
+                purchase_rates: ContractConfig::default().purchase_rates,
+                valid_burn_amounts: vec![20, 40, 60, 80],
+            },
+            seats: old_seats,
+            pending_bets: LookupMap::new(b"p"),
+            pending_moves: LookupMap::new(b"m"),
+            game_state: GameState::WaitingForPlayers,
+            round_number: 7,
+            current_player_seat: None,
+            game_created_at: 1,
+            last_activity: 1,
+            game_config: GameConfigV1 {
+                betting_timeout_ms: 60_000,
+                move_timeout_ms: 30_000,
+                round_break_ms: 5_000,
+                max_inactive_time_ms: 180_000,
+                min_bet_amount: 20,
+                max_bet_amount: 1000,
+                auto_start_delay_ms: 20_000,
+                max_players: Some(3),
+            },
+            blackjack_stats: BlackjackStatsV1 {
+                total_games_played: 7,
+                total_hands_dealt: 9,
+                total_tokens_burned_betting: 300,
+                total_winnings_distributed: 200,
+                total_players_joined: 2,
+            },
+            owner_id: accounts(0),
+            game_admins,
+            is_globally_paused: Some(false),
+            pause_reason: None,
+        });
+    }
+
+    #[test]
+    fn test_migrate_from_v1() {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+        write_v1_state();
+        assert_eq!(stored_state_version(), 1);
+
+        let mut contract = migrate_state();
+        assert_eq!(stored_state_version(), STATE_VERSION);
+        assert_eq!(contract.total_supply, 1000);
+        assert_eq!(contract.round_number, 7);
+        assert_eq!(contract.config.daily_claim_amount, 2000);
+        assert_eq!(contract.config.valid_bet_amounts, vec![20, 40, 60, 80]);
+        assert_eq!(contract.game_config.betting_timeout_ms, 60_000);
+        assert_eq!(contract.blackjack_stats.total_games_played, 7);
+        assert_eq!(contract.treasury_id, accounts(0));
+        assert!(contract.game_admins.get(&accounts(0)).unwrap_or(false));
+
+        // Seats are converted in place along with their indexes
+        let player = contract.seats.get(&1).flatten().unwrap();
+        assert_eq!(player.account_id, accounts(1));
+        assert_eq!(player.rounds_played, 4);
+        assert_eq!(contract.account_to_seat.get(&accounts(1)), Some(1));
+        assert!(contract.seat_storage_reserved.get(&accounts(1)).is_some());
+
+        // Accounts wait for migrate_accounts behind a pause
+        assert_eq!(contract.is_globally_paused, Some(true));
+        assert_eq!(contract.accounts.len(), 2);
+        let result = migrate_accounts(&mut contract, 0, 1);
+        assert_eq!(result.migrated, 1);
+        assert_eq!(result.next_index, Some(1));
+        assert_eq!(contract.is_globally_paused, Some(true));
+
+        // Converted accounts are skipped when a batch is repeated
+        let result = migrate_accounts(&mut contract, 0, 10);
+        assert_eq!(result.migrated, 1);
+        assert_eq!(result.next_index, None);
+        assert_eq!(contract.is_globally_paused, Some(false));
+        assert_eq!(contract.pause_reason, None);
+
+        let user = contract.accounts.get(&accounts(2)).unwrap();
+        assert_eq!(user.balance, 500);
+        assert_eq!(user.alias, None);
+        assert_eq!(migrate_accounts(&mut contract, 0, 10).migrated, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "State is already up to date")]
+    fn test_migrate_current_state() {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+        let contract = CardsContract::new(accounts(0));
+        env::state_write(&contract);
+        migrate_state();
+    }
+}
@@ -3,8 +3,8 @@ use near_sdk::{AccountId, NearToken};
 /// Storage cost constants
 pub const STORAGE_COST_PER_BYTE: u128 = 10_000_000_000_000_000_000; // 1e19 yoctoNEAR per byte
 
-// For typical account names (20-30 chars), storage cost will be ~0.002-0.003 NEAR
-pub const STORAGE_DEPOSIT_REQUIRED: u128 = 12_300_000_000_000_000_000_000; // 0.0123 NEAR minimum
+// For typical account names (20-30 chars), storage cost will be ~0.011 NEAR (account and burn history)
+pub const STORAGE_DEPOSIT_REQUIRED: u128 = 32_300_000_000_000_000_000_000; // 0.0323 NEAR minimum (covers a seat with its session bet history)
 
 /// Helper function to calculate storage cost for a UserAccount
 pub fn calculate_user_storage_cost(account_id: &AccountId) -> NearToken {
@@ -17,12 +17,16 @@ pub fn calculate_user_storage_cost(account_id: &AccountId) -> NearToken {
     let total_purchased_bytes = 16u128; // u128
     let total_burned_bytes = 16u128; // u128
     let registered_at_bytes = 8u128; // u64
+    let alias_tag_bytes = 1u128; // Option<String> tag (alias itself charged separately)
+    let referrer_tag_bytes = 1u128; // Option<AccountId> tag (referrer itself charged separately)
+    let burn_history_bytes = account_id_bytes + 5 + 34 * crate::game::MAX_BURN_HISTORY_RECORDS as u128; // burn_history entry at its cap
     let borsh_overhead = 32u128; // Borsh serialization overhead
     let map_entry_overhead = 64u128; // UnorderedMap entry overhead
     
     let total_bytes = account_id_bytes + balance_bytes + last_claim_time_bytes + 
                      storage_deposited_bytes + total_claimed_bytes + total_purchased_bytes + 
-                     total_burned_bytes + registered_at_bytes + borsh_overhead + map_entry_overhead;
+                     total_burned_bytes + registered_at_bytes + alias_tag_bytes + referrer_tag_bytes +
+                     burn_history_bytes + borsh_overhead + map_entry_overhead;
     
     let cost_yocto = total_bytes * STORAGE_COST_PER_BYTE;
     
@@ -32,25 +36,31 @@ pub fn calculate_user_storage_cost(account_id: &AccountId) -> NearToken {
     NearToken::from_yoctonear(cost_with_margin)
 }
 
-/// Calculate storage cost for SeatPlayer
-pub fn calculate_blackjack_player_storage_cost(account_id: &AccountId) -> NearToken {
+/// Calculate storage cost for SeatPlayer, sized for `max_hands` hands and their burns
+pub fn calculate_blackjack_player_storage_cost(account_id: &AccountId, max_hands: u8) -> NearToken {
     // Estimate bytes for SeatPlayer struct:
     let account_id_bytes = account_id.as_str().len() as u128;
     let seat_number_bytes = 1u128; // u8
     let state_bytes = 4u128; // PlayerState enum
     let current_hand_index_bytes = 1u128; // u8
-    let hands_bytes = 200u128; // Vec<PlayerHand> - estimated for max 2 hands
+    let hands_bytes = 100u128 * max_hands as u128; // Vec<PlayerHand> - estimated per hand
     let total_burned_this_round_bytes = 16u128; // u128
-    let burns_tracking_bytes = 300u128; // Vec<BurnRecord> - estimated
+    let burns_tracking_bytes = 150u128 * max_hands as u128; // Vec<BurnRecord> - bet/split + double per hand
     let joined_at_bytes = 8u128; // u64
     let last_action_time_bytes = 8u128; // u64
+    let last_action_block_bytes = 9u128; // Option<u64>
     let rounds_played_bytes = 4u128; // u32
+    let scoreboard_bytes = 16u128; // wins, losses, pushes, blackjacks (u32 each)
+    let session_bets_bytes = 4u128 + 16 * crate::game::MAX_SESSION_BETS as u128; // Vec<u128> at its cap
+    let seat_index_bytes = account_id_bytes + 1u128; // account_to_seat entry
+    let move_nonce_bytes = 4u128 + 8 * crate::game::MAX_RECENT_MOVE_NONCES as u128; // move_nonces entry (Vec<u64>)
     let borsh_overhead = 32u128; // Borsh serialization overhead
     let vec_entry_overhead = 32u128; // Vec entry overhead
     
     let total_bytes = account_id_bytes + seat_number_bytes + state_bytes + 
                      current_hand_index_bytes + hands_bytes + total_burned_this_round_bytes +
-                     burns_tracking_bytes + joined_at_bytes + last_action_time_bytes + rounds_played_bytes +
+                     burns_tracking_bytes + joined_at_bytes + last_action_time_bytes + last_action_block_bytes +
+                     rounds_played_bytes + scoreboard_bytes + session_bets_bytes + seat_index_bytes + move_nonce_bytes +
                      borsh_overhead + vec_entry_overhead;
     
     let cost_yocto = total_bytes * STORAGE_COST_PER_BYTE;
@@ -81,13 +91,62 @@ pub fn calculate_signals_storage_cost(max_signals: u16) -> NearToken {
 }
 
 
+/// Calculate storage cost for a GiftRecord with the given note
+pub fn calculate_gift_storage_cost(sender_id: &AccountId, receiver_id: &AccountId, note: &str) -> NearToken {
+    // Estimate bytes for GiftRecord struct:
+    let sender_bytes = sender_id.as_str().len() as u128;
+    let amount_bytes = 16u128; // u128
+    let note_bytes = note.len() as u128;
+    let timestamp_bytes = 8u128; // u64
+    let borsh_overhead = 8u128; // String/AccountId length prefixes
+    let entry_key_bytes = 2u128 + receiver_id.as_str().len() as u128 + 8; // "fv" + receiver + index
+    let entry_overhead = 40u128; // Per storage entry overhead
+    
+    let total_bytes = sender_bytes + amount_bytes + note_bytes + timestamp_bytes + borsh_overhead +
+                     entry_key_bytes + entry_overhead;
+    
+    let cost_yocto = total_bytes * STORAGE_COST_PER_BYTE;
+    
+    // Add 20% safety margin for protocol changes
+    let cost_with_margin = cost_yocto * 120 / 100;
+    
+    NearToken::from_yoctonear(cost_with_margin)
+}
+
+/// Calculate storage cost for an alias stored on UserAccount
+pub fn calculate_alias_storage_cost(alias: &str) -> NearToken {
+    let alias_bytes = alias.len() as u128;
+    let borsh_overhead = 4u128; // String length prefix (Option tag is already counted)
+    
+    let cost_yocto = (alias_bytes + borsh_overhead) * STORAGE_COST_PER_BYTE;
+    
+    // Add 20% safety margin for protocol changes
+    let cost_with_margin = cost_yocto * 120 / 100;
+    
+    NearToken::from_yoctonear(cost_with_margin)
+}
+
+/// Calculate storage cost for a referrer stored on UserAccount
+pub fn calculate_referrer_storage_cost(referrer: &AccountId) -> NearToken {
+    let referrer_bytes = referrer.as_str().len() as u128;
+    let borsh_overhead = 4u128; // String length prefix (Option tag is already counted)
+    
+    let cost_yocto = (referrer_bytes + borsh_overhead) * STORAGE_COST_PER_BYTE;
+    
+    // Add 20% safety margin for protocol changes
+    let cost_with_margin = cost_yocto * 120 / 100;
+    
+    NearToken::from_yoctonear(cost_with_margin)
+}
+
 /// Check if user has sufficient storage for blackjack operations
 pub fn has_sufficient_blackjack_storage(
     user_deposit: NearToken, 
-    account_id: &AccountId
+    account_id: &AccountId,
+    max_hands: u8,
 ) -> bool {
     let user_cost = calculate_user_storage_cost(account_id);
-    let player_cost = calculate_blackjack_player_storage_cost(account_id);
+    let player_cost = calculate_blackjack_player_storage_cost(account_id, max_hands);
     let total_required = NearToken::from_yoctonear(
         user_cost.as_yoctonear() + player_cost.as_yoctonear()
     );
@@ -96,9 +155,9 @@ pub fn has_sufficient_blackjack_storage(
 }
 
 /// Get recommended storage deposit for full blackjack functionality
-pub fn recommended_storage_deposit(account_id: &AccountId) -> NearToken {
+pub fn recommended_storage_deposit(account_id: &AccountId, max_hands: u8) -> NearToken {
     let user_cost = calculate_user_storage_cost(account_id);
-    let player_cost = calculate_blackjack_player_storage_cost(account_id);
+    let player_cost = calculate_blackjack_player_storage_cost(account_id, max_hands);
     
     // Add extra buffer for potential future features
     let total_yocto = (user_cost.as_yoctonear() + player_cost.as_yoctonear()) * 150 / 100;
@@ -115,7 +174,7 @@ mod tests {
     fn test_storage_calculations() {
         let account = accounts(1);
         let user_cost = calculate_user_storage_cost(&account);
-        let player_cost = calculate_blackjack_player_storage_cost(&account);
+        let player_cost = calculate_blackjack_player_storage_cost(&account, 2);
         
         // Basic sanity checks
         assert!(user_cost.as_yoctonear() > 0);
@@ -130,13 +189,26 @@ mod tests {
     #[test]
     fn test_storage_sufficiency() {
         let account = accounts(1);
-        let recommended = recommended_storage_deposit(&account);
+        let recommended = recommended_storage_deposit(&account, 2);
         
-        assert!(has_sufficient_blackjack_storage(recommended, &account));
+        assert!(has_sufficient_blackjack_storage(recommended, &account, 2));
         
         let insufficient = NearToken::from_yoctonear(recommended.as_yoctonear() / 2);
-        assert!(!has_sufficient_blackjack_storage(insufficient, &account));
+        assert!(!has_sufficient_blackjack_storage(insufficient, &account, 2));
         
         println!("Recommended storage: {} NEAR", recommended.as_near());
     }
+
+    #[test]
+    fn test_storage_grows_with_max_hands() {
+        let account = accounts(1);
+        let one_hand = calculate_blackjack_player_storage_cost(&account, 1);
+        let two_hands = calculate_blackjack_player_storage_cost(&account, 2);
+        assert!(two_hands > one_hand);
+        
+        // Enough for a single hand is not enough once a second hand is allowed
+        let deposit = calculate_user_storage_cost(&account).saturating_add(one_hand);
+        assert!(has_sufficient_blackjack_storage(deposit, &account, 1));
+        assert!(!has_sufficient_blackjack_storage(deposit, &account, 2));
+    }
 }
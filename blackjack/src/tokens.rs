@@ -1,11 +1,13 @@
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    env, log, require,
+    collections::Vector,
+    assert_one_yocto, env, log, require,
+    json_types::U128,
     serde::{Deserialize, Serialize},
-    AccountId, NearToken, Promise,
+    AccountId, Gas, NearToken, Promise, PromiseResult,
 };
 use schemars::JsonSchema;
-use crate::{CardsContract, events::emit_event};
+use crate::{CardsContract, events::{emit_event, emit_sequenced}};
 
 /// Custom serialization for NearToken to make it JsonSchema compatible
 pub mod near_token_serde {
@@ -64,6 +66,48 @@ pub const MINUTE_IN_NS: u64 = 60_000_000_000; // 1 minute
 pub const HOUR_IN_NS: u64 = 3_600_000_000_000; // 1 hour  
 pub const DAY_IN_NS: u64 = 86_400_000_000_000; // 24 hours
 
+/// Upper bound for the transfer fee (10%)
+pub const MAX_TRANSFER_FEE_BPS: u16 = 1_000;
+
+/// Reason reported when free claims are halted by `max_total_supply`
+pub const CLAIMS_PAUSED_SUPPLY_CAP: &str = "daily claims paused: supply cap reached";
+
+/// Floor for `daily_claim_amount` in whole cards, so claims never mint dust
+pub const MIN_DAILY_CLAIM_AMOUNT: u128 = 100;
+
+/// Highest supported token precision
+pub const MAX_TOKEN_DECIMALS: u8 = 12;
+
+/// Maximum length of a gift note in bytes
+pub const MAX_GIFT_NOTE_LENGTH: usize = 140;
+
+/// Maximum length of a table alias in bytes
+pub const MAX_ALIAS_LENGTH: usize = 32;
+
+/// Words rejected in aliases (case-insensitive substring match)
+pub const BANNED_ALIAS_WORDS: &[&str] = &["admin", "owner", "moderator", "fuck", "shit", "bitch", "cunt"];
+
+/// Maximum referral bonus in basis points (50%)
+pub const MAX_REFERRAL_BONUS_BPS: u16 = 5_000;
+
+/// Maximum accounts checked by one batch eligibility query
+pub const MAX_ELIGIBILITY_BATCH: usize = 50;
+
+/// Maximum accounts examined by one prune_inactive_accounts call
+pub const MAX_PRUNE_BATCH: u64 = 100;
+
+/// Gas reserved for the storage withdrawal callback
+pub const GAS_FOR_WITHDRAW_CALLBACK: Gas = Gas::from_tgas(5);
+
+/// Gas attached to the chat contract's `add_bridged_message`
+pub const GAS_FOR_CHAT_POST: Gas = Gas::from_tgas(20);
+
+/// Gas reserved for the burn-to-chat callback
+pub const GAS_FOR_CHAT_CALLBACK: Gas = Gas::from_tgas(5);
+
+/// Minimum number of distinct bet options kept in `valid_burn_amounts`
+pub const MIN_VALID_BURN_AMOUNTS: usize = 3;
+
 /// User account data
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
@@ -82,6 +126,11 @@ pub struct UserAccount {
     pub total_burned: u128,
     /// Registration timestamp
     pub registered_at: u64,
+    /// Display name shown at the table
+    pub alias: Option<String>,
+    /// Account that referred this user (rewarded on the first purchase)
+    #[schemars(with = "Option<String>")]
+    pub referrer: Option<AccountId>,
 }
 
 /// Contract configuration
@@ -92,10 +141,53 @@ pub struct ContractConfig {
     pub daily_claim_amount: u128,
     /// Claim interval in nanoseconds (1 minute = 60_000_000_000)
     pub claim_interval: u64,
+    /// Claim interval for users who have purchased cards (nanoseconds)
+    pub vip_claim_interval: u64,
     /// Purchase rates (cards per NEAR)
     pub purchase_rates: Vec<PurchaseTier>,
     /// Valid burn amounts
     pub valid_burn_amounts: Vec<u128>,
+    /// Valid blackjack bet denominations
+    pub valid_bet_amounts: Vec<u128>,
+    /// Maximum total supply (None = unlimited)
+    pub max_total_supply: Option<u128>,
+    /// Fee burned from peer transfers, in basis points (0 = disabled)
+    pub transfer_fee_bps: u16,
+    /// Most missed claim intervals `claim_max` pays out at once
+    pub max_catchup_claims: u32,
+    /// Bonus minted to the referrer on a referred user's first purchase, in basis points (0 = disabled)
+    pub referral_bonus_bps: u16,
+    /// New accounts may claim right after registering (false = wait one interval)
+    pub first_claim_immediate: bool,
+    /// Chat contract that burn-to-chat posts are sent to (None = bridge disabled).
+    /// It must list this contract as a bridge; posts are charged to the author's chat deposit
+    #[schemars(with = "Option<String>")]
+    pub chat_contract_id: Option<AccountId>,
+    /// Cards burned for each burn-to-chat post
+    pub chat_burn_amount: u128,
+    /// Token precision: every card amount is stored in units of 10^-decimals cards
+    pub decimals: u8,
+    /// Inclusive range for randomized daily claims (used instead of `daily_claim_amount` when both are set)
+    pub claim_min: Option<u128>,
+    pub claim_max: Option<u128>,
+    /// Largest overpayment refunded on a purchase - deposits above tier cost + this are rejected
+    #[serde(with = "near_token_serde")]
+    #[schemars(with = "String")]
+    pub max_purchase_overpay: NearToken,
+}
+
+/// Maximum gifts returned by one `get_gifts_received` query
+pub const MAX_GIFTS_RECEIVED_LIMIT: u64 = 50;
+
+/// Gift received by an account
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GiftRecord {
+    #[schemars(with = "String")]
+    pub sender_id: AccountId,
+    pub amount: u128,
+    pub note: String,
+    pub timestamp: u64,
 }
 
 /// Purchase tier definition
@@ -112,6 +204,40 @@ pub struct PurchaseTier {
     pub name: String,
 }
 
+/// NEP-148 fungible token metadata
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FungibleTokenMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+    pub decimals: u8,
+}
+
+/// NEP-297 event envelope for the `nep141` standard
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Nep141Event<'a> {
+    pub standard: &'static str,
+    pub version: &'static str,
+    pub event: &'static str,
+    pub data: &'a [FtTransferData<'a>],
+}
+
+/// NEP-141 `ft_transfer` event data
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtTransferData<'a> {
+    pub old_owner_id: &'a AccountId,
+    pub new_owner_id: &'a AccountId,
+    pub amount: U128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
 /// Contract statistics view
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
@@ -123,6 +249,21 @@ pub struct ContractStats {
     pub circulating_supply: u128,
     pub total_users: u64,
     pub active_users: u64, // Users with balance > 0
+    pub max_total_supply: Option<u128>, // None = unlimited
+    pub transfer_fee_bps: u16,
+    pub total_transfer_fees_burned: u128,
+}
+
+/// Circulating supply split by origin, plus cards escrowed in the current round.
+/// Burns are not tracked per source, so live supply is apportioned by the
+/// claimed/purchased mint ratio.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SupplyBreakdown {
+    pub claimed_in_circulation: u128,
+    pub purchased_in_circulation: u128,
+    /// Bets burned this round at all seats, paid back or lost at settlement
+    pub locked_in_bets: u128,
 }
 
 /// User statistics view
@@ -145,6 +286,15 @@ pub struct UserStats {
     pub storage_available: u128, // bytes
 }
 
+/// Outcome of one prune_inactive_accounts batch
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PruneResult {
+    pub pruned: u64,
+    /// `from_index` for the next batch (None once every account was examined)
+    pub next_index: Option<u64>,
+}
+
 /// Claim eligibility check (gas-free view function)
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
@@ -153,10 +303,29 @@ pub struct ClaimEligibility {
     pub reason: String,
     pub next_claim_time: u64,
     pub seconds_until_claim: u64,
+    /// Largest amount one claim can award (`claim_max` when a range is set)
     pub claim_amount: u128,
+    /// Smallest amount one claim can award (equals `claim_amount` for a fixed claim)
+    pub claim_amount_min: u128,
     pub current_balance: u128,
 }
 
+/// Per-account entry of a batch claim eligibility check
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountClaimEligibility {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    pub can_claim: bool,
+    /// Exact time the next claim opens (current time when claimable now, 0 when never)
+    pub next_claim_time: u64,
+    pub seconds_until_claim: u64,
+    pub claim_amount: u128,
+    pub claim_amount_min: u128,
+    /// Purchaser bonus: claims on the shorter `vip_claim_interval`
+    pub vip_interval: bool,
+}
+
 /// Storage management structure
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
@@ -182,12 +351,43 @@ pub struct StorageBounds {
 }
 
 /// Admin configuration update payload
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Serialize, Deserialize, Default, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct AdminConfigUpdate {
     pub daily_claim_amount: Option<u128>,
     pub claim_interval: Option<u64>,
+    pub vip_claim_interval: Option<u64>,
     pub purchase_rates: Option<Vec<PurchaseTier>>,
+    pub valid_burn_amounts: Option<Vec<u128>>,
+    pub max_total_supply: Option<u128>,
+    pub transfer_fee_bps: Option<u16>,
+    pub max_catchup_claims: Option<u32>,
+    pub referral_bonus_bps: Option<u16>,
+    pub first_claim_immediate: Option<bool>,
+    #[schemars(with = "Option<String>")]
+    pub chat_contract_id: Option<AccountId>,
+    pub chat_burn_amount: Option<u128>,
+    /// Randomized claim range bounds (0 clears the bound)
+    pub claim_min: Option<u128>,
+    pub claim_max: Option<u128>,
+    #[serde(default, with = "near_token_option_serde")]
+    #[schemars(with = "Option<String>")]
+    pub max_purchase_overpay: Option<NearToken>,
+}
+
+/// Maximum entries returned by one config history query
+pub const MAX_CONFIG_HISTORY_LIMIT: u64 = 50;
+
+/// One admin configuration change (token or game config)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConfigChange {
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+    #[schemars(with = "String")]
+    pub by: AccountId,
+    pub timestamp: u64,
 }
 
 /// Events for logging
@@ -210,6 +410,27 @@ pub enum CardEvent {
         amount: u128,
         timestamp: u64,
     },
+    Gift {
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        /// Cards sent, before the transfer fee
+        amount: u128,
+        /// Cards credited to the receiver
+        received: u128,
+        timestamp: u64,
+    },
+    ReferralBonus {
+        referrer_id: AccountId,
+        referred_id: AccountId,
+        amount: u128,
+        timestamp: u64,
+    },
+    TreasuryWithdraw {
+        treasury_id: AccountId,
+        receiver_id: AccountId,
+        amount: u128,
+        timestamp: u64,
+    },
     StorageDeposit {
         account_id: AccountId,
         amount: NearToken,
@@ -220,6 +441,12 @@ pub enum CardEvent {
         amount: NearToken,
         timestamp: u64,
     },
+    StorageUnregister {
+        account_id: AccountId,
+        refund: NearToken,
+        cards_burned: u128,
+        timestamp: u64,
+    },
     ConfigUpdate {
         field: String,
         old_value: String,
@@ -239,6 +466,8 @@ impl Default for UserAccount {
             total_purchased: 0,
             total_burned: 0,
             registered_at: env::block_timestamp(),
+            alias: None,
+            referrer: None,
         }
     }
 }
@@ -248,6 +477,7 @@ impl Default for ContractConfig {
         Self {
             daily_claim_amount: 1000,
             claim_interval: MINUTE_IN_NS, 
+            vip_claim_interval: MINUTE_IN_NS,
             purchase_rates: vec![
                 PurchaseTier {
                     near_cost: NearToken::from_near(1),
@@ -271,6 +501,18 @@ impl Default for ContractConfig {
                 },
             ],
             valid_burn_amounts: vec![10, 30, 50, 100],
+            valid_bet_amounts: vec![10, 30, 50, 100],
+            max_total_supply: None,
+            transfer_fee_bps: 0,
+            max_catchup_claims: 7,
+            referral_bonus_bps: 0,
+            first_claim_immediate: true,
+            chat_contract_id: None,
+            chat_burn_amount: 10,
+            decimals: 0,
+            claim_min: None,
+            claim_max: None,
+            max_purchase_overpay: NearToken::from_millinear(100), // 0.1 NEAR
         }
     }
 }
@@ -304,6 +546,10 @@ pub fn storage_deposit(contract: &mut CardsContract, account_id: Option<AccountI
     
     // Create or update user account
     let mut user = contract.accounts.get(&account_id).unwrap_or_default();
+    if !user.storage_deposited && user.last_claim_time == 0 && !contract.config.first_claim_immediate {
+        // The claim interval starts at registration
+        user.last_claim_time = env::block_timestamp();
+    }
     user.storage_deposited = true;
     if user.registered_at == 0 {
         user.registered_at = env::block_timestamp();
@@ -311,7 +557,7 @@ pub fn storage_deposit(contract: &mut CardsContract, account_id: Option<AccountI
     contract.accounts.insert(&account_id, &user);
 
     // Log event
-    emit_event(CardEvent::StorageDeposit {
+    emit_sequenced(contract, CardEvent::StorageDeposit {
         account_id: account_id.clone(),
         amount: deposit,
         timestamp: env::block_timestamp(),
@@ -323,20 +569,20 @@ pub fn storage_deposit(contract: &mut CardsContract, account_id: Option<AccountI
     StorageBalance {
         total: new_total,
         available: NearToken::from_yoctonear(
-            new_total.as_yoctonear().saturating_sub(required_storage.as_yoctonear())
+            new_total.as_yoctonear().saturating_sub(locked_storage(contract, &account_id).as_yoctonear())
         ),
     }
 }
 
-/// Withdraw unused storage deposit
-pub fn storage_withdraw(contract: &mut CardsContract, amount: Option<NearToken>) -> StorageBalance {
-    use crate::storage::calculate_user_storage_cost;
+/// Withdraw unused storage deposit, optionally sending it to another account
+pub fn storage_withdraw(contract: &mut CardsContract, amount: Option<NearToken>, receiver_id: Option<AccountId>) -> StorageBalance {
+    assert_one_yocto();
     
     let account_id = env::predecessor_account_id();
     let current_deposit = contract.storage_deposits.get(&account_id)
         .expect("No storage deposit found");
 
-    let required_storage = calculate_user_storage_cost(&account_id);
+    let required_storage = locked_storage(contract, &account_id);
     let available = current_deposit.as_yoctonear().saturating_sub(required_storage.as_yoctonear());
     let withdraw_amount = amount.map_or(available, |a| a.as_yoctonear().min(available));
     
@@ -346,14 +592,23 @@ pub fn storage_withdraw(contract: &mut CardsContract, amount: Option<NearToken>)
     contract.storage_deposits.insert(&account_id, &new_deposit);
 
     // Log event
-    emit_event(CardEvent::StorageWithdraw {
+    emit_sequenced(contract, CardEvent::StorageWithdraw {
         account_id: account_id.clone(),
         amount: NearToken::from_yoctonear(withdraw_amount),
         timestamp: env::block_timestamp(),
     });
 
-    // Transfer withdrawn amount
-    Promise::new(account_id).transfer(NearToken::from_yoctonear(withdraw_amount));
+    // Transfer withdrawn amount (deduction always comes from the caller's deposit)
+    // and is restored by `on_withdraw_complete` if the transfer fails
+    let receiver_id = receiver_id.unwrap_or_else(|| account_id.clone());
+    log!("Storage withdrawn: {} yoctoNEAR sent to {}", withdraw_amount, receiver_id);
+    Promise::new(receiver_id)
+        .transfer(NearToken::from_yoctonear(withdraw_amount))
+        .then(
+            CardsContract::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_WITHDRAW_CALLBACK)
+                .on_withdraw_complete(account_id, U128(withdraw_amount))
+        );
 
     StorageBalance {
         total: new_deposit,
@@ -361,12 +616,135 @@ pub fn storage_withdraw(contract: &mut CardsContract, amount: Option<NearToken>)
     }
 }
 
+/// Restore a withdrawn storage deposit when its transfer failed
+pub fn on_withdraw_complete(contract: &mut CardsContract, account_id: AccountId, amount: u128) -> bool {
+    if matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+        return true;
+    }
+    
+    let current_deposit = contract.storage_deposits.get(&account_id).unwrap_or(NearToken::from_near(0));
+    contract.storage_deposits.insert(&account_id, &current_deposit.saturating_add(NearToken::from_yoctonear(amount)));
+    
+    log!("Storage withdrawal of {} yoctoNEAR to {} failed - deposit restored", amount, account_id);
+    false
+}
+
+/// Close the caller's account and refund the full storage deposit (NEP-145).
+/// A positive card balance is only allowed with `force`, in which case it is burned.
+pub fn storage_unregister(contract: &mut CardsContract, force: Option<bool>) -> bool {
+    assert_one_yocto();
+    
+    let account_id = env::predecessor_account_id();
+    let force = force.unwrap_or(false);
+
+    let Some(user) = contract.accounts.get(&account_id) else {
+        log!("Account {} is not registered", account_id);
+        return false;
+    };
+
+    // A seat is only given up when no bet is riding on it
+    if let Some(seat_number) = crate::game::player::is_player_seated(contract, &account_id) {
+        let live_bet = contract.seats.get(&seat_number).flatten()
+            .is_some_and(|player| player.total_burned_this_round > 0);
+        require!(!live_bet, "Cannot unregister while holding a seat with a live bet");
+        crate::game::player::leave_seat(contract);
+    }
+
+    require!(
+        user.balance == 0 || force,
+        "Can't unregister the account with a positive balance without force"
+    );
+
+    // Burn any remaining cards
+    let cards_burned = user.balance;
+    if cards_burned > 0 {
+        contract.total_supply = contract.total_supply.checked_sub(cards_burned)
+            .expect("Total supply underflow");
+        contract.total_cards_burned = contract.total_cards_burned.checked_add(cards_burned)
+            .expect("Total cards burned overflow");
+    }
+
+    let refund = remove_account_data(contract, &account_id);
+
+    emit_sequenced(contract, CardEvent::StorageUnregister {
+        account_id: account_id.clone(),
+        refund,
+        cards_burned,
+        timestamp: env::block_timestamp(),
+    });
+
+    if refund.as_yoctonear() > 0 {
+        Promise::new(account_id.clone()).transfer(refund);
+    }
+
+    log!("Account {} unregistered (refund: {} yoctoNEAR, burned: {} cards)",
+        account_id, refund.as_yoctonear(), cards_burned);
+    true
+}
+
+/// Remove everything stored for an account, returning its storage deposit
+fn remove_account_data(contract: &mut CardsContract, account_id: &AccountId) -> NearToken {
+    let refund = contract.storage_deposits.remove(account_id).unwrap_or(NearToken::from_near(0));
+    contract.accounts.remove(account_id);
+    if let Some(mut gifts) = contract.gifts_received.remove(account_id) {
+        gifts.clear();
+    }
+    contract.spectators.remove(account_id);
+    contract.burn_history.remove(account_id);
+    refund
+}
+
+/// Examine up to `limit` accounts starting at `from_index` and close the zero-balance ones
+/// idle for longer than `inactive_ns`, refunding their storage deposits. Seated players are
+/// never pruned. Call again with the returned `next_index` until it is None.
+pub fn prune_inactive_accounts(contract: &mut CardsContract, inactive_ns: u64, from_index: u64, limit: u64) -> PruneResult {
+    let current_time = env::block_timestamp();
+    let limit = limit.min(MAX_PRUNE_BATCH);
+    
+    let mut index = from_index;
+    let mut pruned = 0u64;
+    for _ in 0..limit {
+        let Some(account_id) = contract.accounts.keys_as_vector().get(index) else {
+            break;
+        };
+        let user = contract.accounts.get(&account_id).expect("Account listed without data");
+        
+        // Accounts that never claimed count as active from registration
+        let is_stale = user.balance == 0
+            && current_time.saturating_sub(user.last_claim_time.max(user.registered_at)) > inactive_ns
+            && crate::game::player::is_player_seated(contract, &account_id).is_none();
+        if !is_stale {
+            index += 1;
+            continue;
+        }
+        
+        // Removal swaps the last account into `index`, so it is examined next
+        let refund = remove_account_data(contract, &account_id);
+        pruned += 1;
+        
+        emit_sequenced(contract, CardEvent::StorageUnregister {
+            account_id: account_id.clone(),
+            refund,
+            cards_burned: 0,
+            timestamp: current_time,
+        });
+        
+        if refund.as_yoctonear() > 0 {
+            Promise::new(account_id.clone()).transfer(refund);
+        }
+    }
+    
+    log!("Pruned {} inactive accounts", pruned);
+    PruneResult {
+        pruned,
+        next_index: (index < contract.accounts.len()).then_some(index),
+    }
+}
+
 /// Get storage balance for account
 pub fn storage_balance_of(contract: &CardsContract, account_id: &AccountId) -> Option<StorageBalance> {
-    use crate::storage::calculate_user_storage_cost;
-    
     contract.storage_deposits.get(account_id).map(|total| {
-        let required_storage = calculate_user_storage_cost(account_id);
+        let required_storage = locked_storage(contract, account_id);
         StorageBalance {
             total,
             available: NearToken::from_yoctonear(
@@ -389,8 +767,11 @@ pub fn storage_balance_bounds(_contract: &CardsContract) -> StorageBounds {
 
 /// Claim daily cards
 pub fn claim_daily_cards(contract: &mut CardsContract) -> u128 {
-    let account_id = env::predecessor_account_id();
-    
+    claim_daily_cards_for(contract, env::predecessor_account_id())
+}
+
+/// Claim daily cards into the given account (used directly by relayers)
+pub fn claim_daily_cards_for(contract: &mut CardsContract, account_id: AccountId) -> u128 {
     require!(
         has_sufficient_storage(contract, &account_id),
         "Storage deposit required. Call storage_deposit() first."
@@ -399,42 +780,136 @@ pub fn claim_daily_cards(contract: &mut CardsContract) -> u128 {
     let mut user = contract.accounts.get(&account_id)
         .expect("User account not found");
 
+    require!(
+        contract.config.daily_claim_amount >= MIN_DAILY_CLAIM_AMOUNT * card_unit(contract),
+        "Daily claim amount below minimum"
+    );
+
+    let amount = pick_claim_amount(contract, 0);
+    require!(
+        !is_supply_cap_reached(contract, amount),
+        CLAIMS_PAUSED_SUPPLY_CAP
+    );
+
     let current_time = env::block_timestamp();
     let time_since_last = current_time - user.last_claim_time;
+    let claim_interval = claim_interval_for(contract, &user);
     
     require!(
-        time_since_last >= contract.config.claim_interval,
+        time_since_last >= claim_interval,
         format!("Must wait {} seconds between claims", 
-            (contract.config.claim_interval - time_since_last) / 1_000_000_000)
+            claim_interval.saturating_sub(time_since_last) / 1_000_000_000)
     );
 
     // Update user stats
-    user.balance += contract.config.daily_claim_amount;
+    user.balance += amount;
     user.last_claim_time = current_time;
-    user.total_claimed += contract.config.daily_claim_amount;
+    user.total_claimed += amount;
     
     // Update contract stats
-    contract.total_supply += contract.config.daily_claim_amount;
-    contract.total_cards_claimed += contract.config.daily_claim_amount;
+    contract.total_supply += amount;
+    contract.total_cards_claimed += amount;
     
     // Save user
     contract.accounts.insert(&account_id, &user);
 
     // Log event
-    emit_event(CardEvent::Claim {
+    emit_sequenced(contract, CardEvent::Claim {
         account_id: account_id.clone(),
-        amount: contract.config.daily_claim_amount,
+        amount,
         timestamp: current_time,
     });
 
-    log!("Daily claim: {} cards claimed by {}", contract.config.daily_claim_amount, account_id);
+    log!("Daily claim: {} cards claimed by {}", amount, account_id);
+
+    amount
+}
+
+/// Inclusive `(min, max)` a single daily claim can award
+fn claim_range(contract: &CardsContract) -> (u128, u128) {
+    match (contract.config.claim_min, contract.config.claim_max) {
+        (Some(min), Some(max)) => (min, max),
+        _ => (contract.config.daily_claim_amount, contract.config.daily_claim_amount),
+    }
+}
+
+/// Amount for one daily claim: drawn from `claim_min..=claim_max` when both are set.
+/// `draw` tells apart several claims paid in the same call
+fn pick_claim_amount(contract: &CardsContract, draw: u64) -> u128 {
+    let (Some(min), Some(max)) = (contract.config.claim_min, contract.config.claim_max) else {
+        return contract.config.daily_claim_amount;
+    };
+    if min == max {
+        return min;
+    }
+    
+    let mut entropy = env::random_seed();
+    entropy.extend_from_slice(&draw.to_le_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&env::sha256(&entropy)[..16]);
+    let span = max - min + 1; // min >= MIN_DAILY_CLAIM_AMOUNT, so this cannot overflow
+    min + u128::from_le_bytes(bytes) % span
+}
+
+/// Claim every missed interval since the last claim (up to `max_catchup_claims`) in one call
+pub fn claim_max(contract: &mut CardsContract) -> u128 {
+    let account_id = env::predecessor_account_id();
+    require!(
+        has_sufficient_storage(contract, &account_id),
+        "Storage deposit required. Call storage_deposit() first."
+    );
+
+    let mut user = contract.accounts.get(&account_id)
+        .expect("User account not found");
+
+    require!(
+        contract.config.daily_claim_amount >= MIN_DAILY_CLAIM_AMOUNT * card_unit(contract),
+        "Daily claim amount below minimum"
+    );
+
+    let current_time = env::block_timestamp();
+    let time_since_last = current_time.saturating_sub(user.last_claim_time);
+    let claim_interval = claim_interval_for(contract, &user).max(1);
+    // An account that never claimed has nothing to catch up on: it gets a single claim
+    let never_claimed = user.last_claim_time == 0;
+    let elapsed_intervals = if never_claimed { 1 } else { time_since_last / claim_interval };
+    
+    require!(
+        elapsed_intervals >= 1,
+        format!("Must wait {} seconds between claims", 
+            claim_interval.saturating_sub(time_since_last) / 1_000_000_000)
+    );
+
+    let claims = elapsed_intervals.min(contract.config.max_catchup_claims as u64);
+    let amount: u128 = (0..claims).map(|draw| pick_claim_amount(contract, draw)).sum();
+    require!(!is_supply_cap_reached(contract, amount), CLAIMS_PAUSED_SUPPLY_CAP);
 
-    contract.config.daily_claim_amount
+    // Keep progress toward the next interval; intervals beyond the cap are forfeited
+    user.last_claim_time = if never_claimed || elapsed_intervals > claims {
+        current_time
+    } else {
+        user.last_claim_time + claims * claim_interval
+    };
+    user.balance += amount;
+    user.total_claimed += amount;
+    
+    contract.total_supply += amount;
+    contract.total_cards_claimed += amount;
+    contract.accounts.insert(&account_id, &user);
+
+    emit_sequenced(contract, CardEvent::Claim {
+        account_id: account_id.clone(),
+        amount,
+        timestamp: current_time,
+    });
+
+    log!("Catch-up claim: {} cards ({} intervals) claimed by {}", amount, claims, account_id);
+    amount
 }
 
 /// Purchase cards with NEAR deposit
 /// tier_index: 0=Basic, 1=Value, 2=Premium, 3=Ultimate
-pub fn purchase_cards(contract: &mut CardsContract, tier_index: u8) -> u128 {
+pub fn purchase_cards(contract: &mut CardsContract, tier_index: u8, expected_cost: Option<NearToken>) -> u128 {
     let account_id = env::predecessor_account_id();
     
     require!(
@@ -448,9 +923,32 @@ pub fn purchase_cards(contract: &mut CardsContract, tier_index: u8) -> u128 {
         format!("Invalid tier index {}. Valid range: 0-{}", tier_index, contract.config.purchase_rates.len() - 1)
     );
     
-    let tier = &contract.config.purchase_rates[tier_index as usize];
+    let tier = contract.config.purchase_rates[tier_index as usize].clone();
     let deposit = env::attached_deposit();
 
+    // Tiers may have shifted since the caller read them: refund rather than sell another tier
+    if expected_cost.is_some_and(|cost| cost != tier.near_cost) {
+        log!("Purchase refunded: tier {} now costs {} yoctoNEAR, caller expected {:?}",
+            tier_index, tier.near_cost.as_yoctonear(), expected_cost.map(|cost| cost.as_yoctonear()));
+        if !deposit.is_zero() {
+            Promise::new(account_id).transfer(deposit);
+        }
+        return 0;
+    }
+
+    // First purchase by a referred user also mints the referrer's bonus
+    let mut user = contract.accounts.get(&account_id).unwrap_or_default();
+    let referral_bonus = match &user.referrer {
+        Some(_) if user.total_purchased == 0 =>
+            tier.cards_amount * contract.config.referral_bonus_bps as u128 / 10_000,
+        _ => 0,
+    };
+
+    require!(
+        !is_supply_cap_reached(contract, tier.cards_amount + referral_bonus),
+        "Purchase would exceed max total supply"
+    );
+
     // Verify the attached deposit matches the tier cost
     require!(
         deposit >= tier.near_cost,
@@ -458,10 +956,15 @@ pub fn purchase_cards(contract: &mut CardsContract, tier_index: u8) -> u128 {
             tier.near_cost.as_near(), 
             deposit.as_near())
     );
+    // Reject large overpayments (likely a wrong tier) instead of silently refunding them
+    require!(
+        deposit.saturating_sub(tier.near_cost) <= contract.config.max_purchase_overpay,
+        format!("Deposit exceeds tier cost by more than the allowed tolerance. Required: {} NEAR, Attached: {} NEAR",
+            tier.near_cost.as_near(),
+            deposit.as_near())
+    );
 
     // CRITICAL FIX: Update state BEFORE external calls to prevent re-entrancy
-    // Get or create user
-    let mut user = contract.accounts.get(&account_id).unwrap_or_default();
     if !user.storage_deposited {
         user.storage_deposited = true;
         user.registered_at = env::block_timestamp();
@@ -482,6 +985,12 @@ pub fn purchase_cards(contract: &mut CardsContract, tier_index: u8) -> u128 {
     // Save user BEFORE external calls
     contract.accounts.insert(&account_id, &user);
 
+    if referral_bonus > 0 {
+        if let Some(referrer_id) = &user.referrer {
+            pay_referral_bonus(contract, &account_id, referrer_id, referral_bonus);
+        }
+    }
+
     // EXTERNAL CALLS AFTER STATE CHANGES
     // If user overpaid, refund the excess
     if deposit > tier.near_cost {
@@ -493,7 +1002,7 @@ pub fn purchase_cards(contract: &mut CardsContract, tier_index: u8) -> u128 {
     Promise::new(contract.owner_id.clone()).transfer(tier.near_cost);
 
     // Log event
-    emit_event(CardEvent::Purchase {
+    emit_sequenced(contract, CardEvent::Purchase {
         account_id: account_id.clone(),
         amount: tier.cards_amount,
         cost: tier.near_cost,
@@ -543,7 +1052,7 @@ pub fn burn_cards(contract: &mut CardsContract, amount: u128) {
     contract.accounts.insert(&account_id, &user);
 
     // Log event
-    emit_event(CardEvent::Burn {
+    emit_sequenced(contract, CardEvent::Burn {
         account_id: account_id.clone(),
         amount,
         timestamp: env::block_timestamp(),
@@ -552,66 +1061,427 @@ pub fn burn_cards(contract: &mut CardsContract, amount: u128) {
     log!("Burn: {} cards burned by {}", amount, account_id);
 }
 
-// ========================================
-// VIEW FUNCTIONS
-// ========================================
+/// Burn `chat_burn_amount` cards and post the message to the chat contract.
+/// The chat contract sees this contract as the author and charges its chat storage
+/// deposit, so the message is prefixed with the caller's account.
+/// `on_chat_post_complete` re-mints the burn if the post fails
+pub fn post_chat_with_burn(contract: &mut CardsContract, message: String) -> Promise {
+    let account_id = env::predecessor_account_id();
+    let chat_contract_id = contract.config.chat_contract_id.clone()
+        .unwrap_or_else(|| env::panic_str("Chat bridge is not configured"));
+    require!(!message.is_empty(), "Message cannot be empty");
+    
+    let amount = contract.config.chat_burn_amount;
+    let mut user = contract.accounts.get(&account_id)
+        .expect("User account not found");
+    require!(user.balance >= amount, "Insufficient card balance");
+    
+    // Burn before the external call so a failed post can only be refunded, never replayed
+    user.balance -= amount;
+    user.total_burned = user.total_burned.checked_add(amount)
+        .expect("Total burned overflow");
+    contract.total_supply = contract.total_supply.checked_sub(amount)
+        .expect("Total supply underflow");
+    contract.total_cards_burned = contract.total_cards_burned.checked_add(amount)
+        .expect("Total cards burned overflow");
+    contract.accounts.insert(&account_id, &user);
+    
+    emit_sequenced(contract, CardEvent::Burn {
+        account_id: account_id.clone(),
+        amount,
+        timestamp: env::block_timestamp(),
+    });
+    
+    log!("Chat burn: {} cards burned by {} to post on {}", amount, account_id, chat_contract_id);
+    // Posted as the author, so chat applies its ban list and charges the author's own deposit
+    let args = near_sdk::serde_json::json!({ "author": account_id, "message": message });
+    Promise::new(chat_contract_id)
+        .function_call(
+            "add_bridged_message".to_string(),
+            args.to_string().into_bytes(),
+            NearToken::from_near(0),
+            GAS_FOR_CHAT_POST,
+        )
+        .then(
+            CardsContract::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_CHAT_CALLBACK)
+                .on_chat_post_complete(account_id, U128(amount))
+        )
+}
 
-/// Check if user can claim cards (gas-free)
-pub fn check_claim_eligibility(contract: &CardsContract, account_id: &AccountId) -> ClaimEligibility {
-    let current_time = env::block_timestamp();
+/// Re-mint a burn-to-chat burn when the chat post failed
+pub fn on_chat_post_complete(contract: &mut CardsContract, account_id: AccountId, amount: u128) -> bool {
+    if matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+        return true;
+    }
     
-    if let Some(user) = contract.accounts.get(account_id) {
-        if !user.storage_deposited {
-            return ClaimEligibility {
-                can_claim: false,
-                reason: "Storage deposit required. Call storage_deposit() first.".to_string(),
-                next_claim_time: 0,
-                seconds_until_claim: 0,
-                claim_amount: 0,
-                current_balance: user.balance,
-            };
-        }
-        
-        let time_since_last = current_time - user.last_claim_time;
-        if time_since_last < contract.config.claim_interval {
-            let next_claim = user.last_claim_time + contract.config.claim_interval;
-            let seconds_remaining = (next_claim - current_time) / 1_000_000_000;
-            
-            return ClaimEligibility {
-                can_claim: false,
-                reason: format!("Must wait {} seconds between claims", seconds_remaining),
-                next_claim_time: next_claim,
-                seconds_until_claim: seconds_remaining,
-                claim_amount: contract.config.daily_claim_amount,
-                current_balance: user.balance,
-            };
-        }
-        
-        ClaimEligibility {
-            can_claim: true,
-            reason: "Ready to claim!".to_string(),
-            next_claim_time: current_time + contract.config.claim_interval,
-            seconds_until_claim: 0,
-            claim_amount: contract.config.daily_claim_amount,
-            current_balance: user.balance,
-        }
-        
-    } else {
-        ClaimEligibility {
-            can_claim: false,
-            reason: "Account not registered. Call storage_deposit() first.".to_string(),
-            next_claim_time: 0,
-            seconds_until_claim: 0,
-            claim_amount: 0,
-            current_balance: 0,
-        }
+    if let Some(mut user) = contract.accounts.get(&account_id) {
+        user.balance = user.balance.checked_add(amount)
+            .expect("Balance overflow in on_chat_post_complete");
+        user.total_burned = user.total_burned.saturating_sub(amount);
+        contract.accounts.insert(&account_id, &user);
+        contract.total_supply = contract.total_supply.checked_add(amount)
+            .expect("Total supply overflow");
+        contract.total_cards_burned = contract.total_cards_burned.saturating_sub(amount);
     }
+    
+    log!("Chat post by {} failed - {} burned cards restored", account_id, amount);
+    false
 }
 
-/// Get user card balance
-pub fn get_balance(contract: &CardsContract, account_id: &AccountId) -> u128 {
-    contract.accounts.get(account_id)
-        .map_or(0, |user| user.balance)
+/// Gift cards to another registered account with a short note
+pub fn gift_cards(contract: &mut CardsContract, receiver_id: AccountId, amount: u128, note: String) {
+    use crate::storage::calculate_gift_storage_cost;
+    
+    let sender_id = env::predecessor_account_id();
+    let timestamp = env::block_timestamp();
+    
+    require!(amount > 0, "Gift amount must be positive");
+    require!(sender_id != receiver_id, "Cannot gift cards to yourself");
+    require!(
+        note.len() <= MAX_GIFT_NOTE_LENGTH,
+        format!("Gift note too long (max {} characters)", MAX_GIFT_NOTE_LENGTH)
+    );
+    require!(contract.accounts.get(&receiver_id).is_some(), "Receiver is not registered");
+
+    // Charge the note's storage to the sender's storage deposit
+    let storage_cost = calculate_gift_storage_cost(&sender_id, &receiver_id, &note);
+    let sender_deposit = contract.storage_deposits.get(&sender_id).unwrap_or(NearToken::from_near(0));
+    let available = sender_deposit.as_yoctonear()
+        .saturating_sub(locked_storage(contract, &sender_id).as_yoctonear());
+    require!(
+        available >= storage_cost.as_yoctonear(),
+        format!("Insufficient storage deposit for gift note. Required: {} yoctoNEAR", storage_cost.as_yoctonear())
+    );
+    contract.storage_deposits.insert(
+        &sender_id,
+        &NearToken::from_yoctonear(sender_deposit.as_yoctonear() - storage_cost.as_yoctonear()),
+    );
+
+    // Move the cards (net of any transfer fee)
+    let received = internal_transfer(contract, &sender_id, &receiver_id, amount);
+
+    // Record the gift for the receiver
+    let mut gifts = contract.gifts_received.get(&receiver_id).unwrap_or_else(|| {
+        Vector::new([b"fv".as_slice(), receiver_id.as_bytes()].concat())
+    });
+    gifts.push(&GiftRecord {
+        sender_id: sender_id.clone(),
+        amount: received,
+        note,
+        timestamp,
+    });
+    contract.gifts_received.insert(&receiver_id, &gifts);
+
+    emit_sequenced(contract, CardEvent::Gift {
+        sender_id: sender_id.clone(),
+        receiver_id: receiver_id.clone(),
+        amount,
+        received,
+        timestamp,
+    });
+
+    log!("Gift: {} cards from {} to {}", received, sender_id, receiver_id);
+}
+
+/// Set or clear the caller's table alias (storage covered by their deposit)
+pub fn set_alias(contract: &mut CardsContract, alias: Option<String>) {
+    use crate::storage::calculate_alias_storage_cost;
+    
+    let account_id = env::predecessor_account_id();
+    let mut user = contract.accounts.get(&account_id)
+        .expect("User account not found. Call storage_deposit() first.");
+
+    let alias = alias.map(|alias| alias.trim().to_string());
+    if let Some(alias) = &alias {
+        require!(!alias.is_empty(), "Alias cannot be empty");
+        require!(
+            alias.len() <= MAX_ALIAS_LENGTH,
+            format!("Alias too long (max {} characters)", MAX_ALIAS_LENGTH)
+        );
+        let lowered = alias.to_lowercase();
+        require!(
+            !BANNED_ALIAS_WORDS.iter().any(|word| lowered.contains(word)),
+            "Alias contains a banned word"
+        );
+
+        // Deposit must cover everything already locked plus the new alias
+        let deposit = contract.storage_deposits.get(&account_id).unwrap_or(NearToken::from_near(0));
+        let old_alias_cost = user.alias.as_deref().map_or(0, |old| calculate_alias_storage_cost(old).as_yoctonear());
+        let required = locked_storage(contract, &account_id).as_yoctonear() - old_alias_cost
+            + calculate_alias_storage_cost(alias).as_yoctonear();
+        require!(
+            deposit.as_yoctonear() >= required,
+            format!("Insufficient storage deposit for alias. Required: {} yoctoNEAR", required)
+        );
+    }
+
+    user.alias = alias;
+    contract.accounts.insert(&account_id, &user);
+
+    log!("Alias for {} set to {:?}", account_id, user.alias);
+}
+
+/// Record who referred the caller (once, before their first purchase)
+pub fn register_with_referrer(contract: &mut CardsContract, referrer: AccountId) {
+    use crate::storage::calculate_referrer_storage_cost;
+    
+    let account_id = env::predecessor_account_id();
+    let mut user = contract.accounts.get(&account_id)
+        .expect("User account not found. Call storage_deposit() first.");
+    
+    require!(referrer != account_id, "Cannot refer yourself");
+    require!(user.referrer.is_none(), "Referrer already set");
+    require!(user.total_purchased == 0, "Referrer must be set before the first purchase");
+    require!(contract.accounts.get(&referrer).is_some(), "Referrer is not registered");
+    
+    // Walk the referrer's chain so no referral loop can form
+    let mut ancestor = contract.accounts.get(&referrer).and_then(|user| user.referrer);
+    while let Some(current) = ancestor {
+        require!(current != account_id, "Referral loop detected");
+        ancestor = contract.accounts.get(&current).and_then(|user| user.referrer);
+    }
+    
+    // Deposit must cover everything already locked plus the referrer id
+    let deposit = contract.storage_deposits.get(&account_id).unwrap_or(NearToken::from_near(0));
+    let required = locked_storage(contract, &account_id).as_yoctonear()
+        + calculate_referrer_storage_cost(&referrer).as_yoctonear();
+    require!(
+        deposit.as_yoctonear() >= required,
+        format!("Insufficient storage deposit for referrer. Required: {} yoctoNEAR", required)
+    );
+    
+    user.referrer = Some(referrer.clone());
+    contract.accounts.insert(&account_id, &user);
+    
+    log!("{} registered with referrer {}", account_id, referrer);
+}
+
+/// Mint the referral bonus for a referred user's first purchase
+fn pay_referral_bonus(contract: &mut CardsContract, referred_id: &AccountId, referrer_id: &AccountId, bonus: u128) {
+    let Some(mut referrer) = contract.accounts.get(referrer_id) else {
+        log!("Referrer {} no longer registered - bonus skipped", referrer_id);
+        return;
+    };
+    
+    referrer.balance = referrer.balance.checked_add(bonus)
+        .expect("Balance overflow in referral bonus");
+    contract.accounts.insert(referrer_id, &referrer);
+    contract.total_supply = contract.total_supply.checked_add(bonus)
+        .expect("Total supply overflow");
+    
+    emit_sequenced(contract, CardEvent::ReferralBonus {
+        referrer_id: referrer_id.clone(),
+        referred_id: referred_id.clone(),
+        amount: bonus,
+        timestamp: env::block_timestamp(),
+    });
+    
+    log!("Referral bonus: {} cards to {} for referring {}", bonus, referrer_id, referred_id);
+}
+
+/// Move cards collected by the treasury (e.g. rake) to the owner (Owner only).
+/// A plain balance move: no transfer fee and total_supply is unchanged
+pub fn withdraw_treasury_tokens(contract: &mut CardsContract, amount: u128) {
+    let caller = env::predecessor_account_id();
+    require!(caller == contract.owner_id, "Only contract owner can call this method");
+    require!(amount > 0, "Amount must be positive");
+    
+    let treasury_id = contract.treasury_id.clone();
+    let owner_id = contract.owner_id.clone();
+    require!(treasury_id != owner_id, "Treasury is the owner account, nothing to withdraw");
+    
+    let mut treasury = contract.accounts.get(&treasury_id).expect("Treasury account not found");
+    let mut owner = contract.accounts.get(&owner_id).expect("Owner account not registered");
+    require!(
+        treasury.balance >= amount,
+        format!("Treasury holds only {} cards", treasury.balance)
+    );
+    
+    treasury.balance -= amount;
+    owner.balance = owner.balance.checked_add(amount)
+        .expect("Balance overflow in treasury withdrawal");
+    contract.accounts.insert(&treasury_id, &treasury);
+    contract.accounts.insert(&owner_id, &owner);
+    
+    emit_sequenced(contract, CardEvent::TreasuryWithdraw {
+        treasury_id: treasury_id.clone(),
+        receiver_id: owner_id,
+        amount,
+        timestamp: env::block_timestamp(),
+    });
+    
+    log!("Treasury withdrawal: {} cards from {}", amount, treasury_id);
+}
+
+/// NEP-141 transfer between registered accounts (requires exactly 1 yoctoNEAR)
+pub fn ft_transfer(contract: &mut CardsContract, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+    assert_one_yocto();
+    
+    let sender_id = env::predecessor_account_id();
+    let amount = amount.0;
+    
+    require!(amount > 0, "The amount should be a positive number");
+    require!(sender_id != receiver_id, "Sender and receiver should be different");
+    require!(
+        contract.storage_deposits.get(&receiver_id).is_some(),
+        "Receiver is not registered"
+    );
+
+    let received = internal_transfer(contract, &sender_id, &receiver_id, amount);
+
+    emit_event(Nep141Event {
+        standard: "nep141",
+        version: "1.0.0",
+        event: "ft_transfer",
+        data: &[FtTransferData {
+            old_owner_id: &sender_id,
+            new_owner_id: &receiver_id,
+            amount: U128(received),
+            memo: memo.as_deref(),
+        }],
+    });
+
+    log!("Transfer {} cards from {} to {}", amount, sender_id, receiver_id);
+}
+
+/// Move cards between two registered accounts, burning `transfer_fee_bps` of the amount.
+/// Returns the amount credited to the receiver.
+pub fn internal_transfer(contract: &mut CardsContract, sender_id: &AccountId, receiver_id: &AccountId, amount: u128) -> u128 {
+    let mut sender = contract.accounts.get(sender_id)
+        .expect("Sender account not found");
+    let mut receiver = contract.accounts.get(receiver_id)
+        .expect("Receiver account not found");
+
+    require!(sender.balance >= amount, "Insufficient card balance");
+
+    let fee = amount * contract.config.transfer_fee_bps as u128 / 10_000;
+    let net_amount = amount - fee;
+
+    sender.balance = sender.balance.checked_sub(amount)
+        .expect("Balance underflow in transfer");
+    receiver.balance = receiver.balance.checked_add(net_amount)
+        .expect("Balance overflow in transfer");
+
+    contract.accounts.insert(sender_id, &sender);
+    contract.accounts.insert(receiver_id, &receiver);
+
+    // Fee leaves circulation
+    if fee > 0 {
+        contract.total_supply = contract.total_supply.checked_sub(fee)
+            .expect("Total supply underflow");
+        contract.total_transfer_fees_burned = contract.total_transfer_fees_burned.checked_add(fee)
+            .expect("Total transfer fees overflow");
+        log!("Transfer fee: {} cards burned", fee);
+    }
+
+    net_amount
+}
+
+// ========================================
+// VIEW FUNCTIONS
+// ========================================
+
+/// Check if user can claim cards (gas-free)
+pub fn check_claim_eligibility(contract: &CardsContract, account_id: &AccountId) -> ClaimEligibility {
+    let current_time = env::block_timestamp();
+    
+    if let Some(user) = contract.accounts.get(account_id) {
+        if !user.storage_deposited {
+            return ClaimEligibility {
+                can_claim: false,
+                reason: "Storage deposit required. Call storage_deposit() first.".to_string(),
+                next_claim_time: 0,
+                seconds_until_claim: 0,
+                claim_amount: 0,
+                claim_amount_min: 0,
+                current_balance: user.balance,
+            };
+        }
+        
+        let (claim_amount_min, claim_amount) = claim_range(contract);
+        if is_supply_cap_reached(contract, claim_amount) {
+            return ClaimEligibility {
+                can_claim: false,
+                reason: CLAIMS_PAUSED_SUPPLY_CAP.to_string(),
+                next_claim_time: 0,
+                seconds_until_claim: 0,
+                claim_amount: 0,
+                claim_amount_min: 0,
+                current_balance: user.balance,
+            };
+        }
+        
+        let claim_interval = claim_interval_for(contract, &user);
+        let time_since_last = current_time - user.last_claim_time;
+        if time_since_last < claim_interval {
+            let next_claim = user.last_claim_time + claim_interval;
+            let seconds_remaining = (next_claim - current_time) / 1_000_000_000;
+            
+            return ClaimEligibility {
+                can_claim: false,
+                reason: format!("Must wait {} seconds between claims", seconds_remaining),
+                next_claim_time: next_claim,
+                seconds_until_claim: seconds_remaining,
+                claim_amount,
+                claim_amount_min,
+                current_balance: user.balance,
+            };
+        }
+        
+        ClaimEligibility {
+            can_claim: true,
+            reason: "Ready to claim!".to_string(),
+            next_claim_time: current_time + claim_interval,
+            seconds_until_claim: 0,
+            claim_amount,
+            claim_amount_min,
+            current_balance: user.balance,
+        }
+        
+    } else {
+        ClaimEligibility {
+            can_claim: false,
+            reason: "Account not registered. Call storage_deposit() first.".to_string(),
+            next_claim_time: 0,
+            seconds_until_claim: 0,
+            claim_amount: 0,
+            claim_amount_min: 0,
+            current_balance: 0,
+        }
+    }
+}
+
+/// Check claim eligibility for several accounts at once (roster countdowns)
+pub fn check_claim_eligibility_many(contract: &CardsContract, account_ids: Vec<AccountId>) -> Vec<AccountClaimEligibility> {
+    require!(
+        account_ids.len() <= MAX_ELIGIBILITY_BATCH,
+        format!("Too many accounts. Maximum: {}", MAX_ELIGIBILITY_BATCH)
+    );
+    let current_time = env::block_timestamp();
+    
+    account_ids.into_iter().map(|account_id| {
+        let eligibility = check_claim_eligibility(contract, &account_id);
+        let next_claim_time = if eligibility.can_claim { current_time } else { eligibility.next_claim_time };
+        let vip_interval = contract.accounts.get(&account_id)
+            .is_some_and(|user| user.total_purchased > 0);
+        
+        AccountClaimEligibility {
+            account_id,
+            can_claim: eligibility.can_claim,
+            next_claim_time,
+            seconds_until_claim: eligibility.seconds_until_claim,
+            claim_amount: eligibility.claim_amount,
+            claim_amount_min: eligibility.claim_amount_min,
+            vip_interval,
+        }
+    }).collect()
+}
+
+/// Get user card balance
+pub fn get_balance(contract: &CardsContract, account_id: &AccountId) -> u128 {
+    contract.accounts.get(account_id)
+        .map_or(0, |user| user.balance)
 }
 
 /// Get detailed user statistics
@@ -628,7 +1498,7 @@ pub fn get_user_stats(contract: &CardsContract, account_id: &AccountId) -> Optio
     Some(UserStats {
         balance: user.balance,
         last_claim_time: user.last_claim_time,
-        next_claim_available: user.last_claim_time + contract.config.claim_interval,
+        next_claim_available: user.last_claim_time + claim_interval_for(contract, &user),
         can_claim_now: can_user_claim(contract, account_id),
         total_claimed: user.total_claimed,
         total_purchased: user.total_purchased,
@@ -641,6 +1511,16 @@ pub fn get_user_stats(contract: &CardsContract, account_id: &AccountId) -> Optio
     })
 }
 
+/// Leaderboard position by total cards claimed (1 = top; ties share a rank).
+/// Scans every account, so gas grows linearly with the number of registered users
+pub fn get_user_rank(contract: &CardsContract, account_id: &AccountId) -> Option<u64> {
+    let claimed = contract.accounts.get(account_id)?.total_claimed;
+    let ahead = contract.accounts.values()
+        .filter(|user| user.total_claimed > claimed)
+        .count() as u64;
+    Some(ahead + 1)
+}
+
 /// Get contract statistics
 pub fn get_contract_stats(contract: &CardsContract) -> ContractStats {
     let mut active_users = 0;
@@ -661,7 +1541,108 @@ pub fn get_contract_stats(contract: &CardsContract) -> ContractStats {
         circulating_supply: contract.total_supply.saturating_sub(contract.total_cards_burned),
         total_users,
         active_users,
+        max_total_supply: contract.config.max_total_supply,
+        transfer_fee_bps: contract.config.transfer_fee_bps,
+        total_transfer_fees_burned: contract.total_transfer_fees_burned,
+    }
+}
+
+/// Get circulating supply by origin and the amount locked in active bets
+pub fn get_supply_breakdown(contract: &CardsContract) -> SupplyBreakdown {
+    let minted = contract.total_cards_claimed + contract.total_cards_purchased;
+    let claimed_in_circulation = if minted == 0 {
+        0
+    } else {
+        contract.total_supply.saturating_mul(contract.total_cards_claimed) / minted
+    };
+    let locked_in_bets = (1..=3)
+        .filter_map(|seat| contract.seats.get(&seat).flatten())
+        .map(|player| player.total_burned_this_round)
+        .sum();
+    
+    SupplyBreakdown {
+        claimed_in_circulation,
+        purchased_in_circulation: if minted == 0 { 0 } else { contract.total_supply - claimed_in_circulation },
+        locked_in_bets,
+    }
+}
+
+/// Get gifts received by an account (newest first)
+pub fn get_gifts_received(contract: &CardsContract, account_id: &AccountId, limit: Option<u64>) -> Vec<GiftRecord> {
+    let Some(gifts) = contract.gifts_received.get(account_id) else {
+        return Vec::new();
+    };
+    let limit = limit.unwrap_or(MAX_GIFTS_RECEIVED_LIMIT).min(MAX_GIFTS_RECEIVED_LIMIT);
+    
+    (gifts.len().saturating_sub(limit)..gifts.len())
+        .rev()
+        .filter_map(|index| gifts.get(index))
+        .collect()
+}
+
+/// Get NEP-148 token metadata
+pub fn get_ft_metadata(contract: &CardsContract) -> FungibleTokenMetadata {
+    FungibleTokenMetadata {
+        spec: "ft-1.0.0".to_string(),
+        name: "Wars of Cards".to_string(),
+        symbol: "CARDS".to_string(),
+        icon: None,
+        reference: None,
+        reference_hash: None,
+        decimals: contract.config.decimals,
+    }
+}
+
+/// Internal units in one whole card
+pub fn card_unit(contract: &CardsContract) -> u128 {
+    10u128.pow(contract.config.decimals as u32)
+}
+
+/// Change token precision before any cards exist (Owner only). Every card-denominated
+/// setting is rescaled so it keeps its value in whole cards
+pub fn set_decimals(contract: &mut CardsContract, decimals: u8) {
+    let caller = env::predecessor_account_id();
+    require!(caller == contract.owner_id, "Only contract owner can call this method");
+    require!(
+        decimals <= MAX_TOKEN_DECIMALS,
+        format!("Decimals cannot exceed {}", MAX_TOKEN_DECIMALS)
+    );
+    require!(
+        contract.total_supply == 0 && contract.total_cards_burned == 0,
+        "Decimals can only change before any cards are minted"
+    );
+    
+    let old_decimals = contract.config.decimals;
+    let scale = |amount: u128| -> u128 {
+        if decimals >= old_decimals {
+            amount.checked_mul(10u128.pow((decimals - old_decimals) as u32))
+                .unwrap_or_else(|| env::panic_str("Amount overflow while rescaling"))
+        } else {
+            let divisor = 10u128.pow((old_decimals - decimals) as u32);
+            require!(amount % divisor == 0, format!("{} cannot be expressed with {} decimals", amount, decimals));
+            amount / divisor
+        }
+    };
+    
+    let config = &mut contract.config;
+    config.daily_claim_amount = scale(config.daily_claim_amount);
+    config.valid_burn_amounts = config.valid_burn_amounts.iter().map(|amount| scale(*amount)).collect();
+    config.valid_bet_amounts = config.valid_bet_amounts.iter().map(|amount| scale(*amount)).collect();
+    config.max_total_supply = config.max_total_supply.map(scale);
+    config.chat_burn_amount = scale(config.chat_burn_amount);
+    config.claim_min = config.claim_min.map(scale);
+    config.claim_max = config.claim_max.map(scale);
+    for tier in config.purchase_rates.iter_mut() {
+        tier.cards_amount = scale(tier.cards_amount);
     }
+    config.decimals = decimals;
+    
+    let game_config = &mut contract.game_config;
+    game_config.min_bet_amount = scale(game_config.min_bet_amount);
+    game_config.max_bet_amount = scale(game_config.max_bet_amount);
+    game_config.max_burn_per_round = game_config.max_burn_per_round.map(scale);
+    
+    record_config_change(contract, "decimals", old_decimals.to_string(), decimals.to_string());
 }
 
 /// Get purchase tiers
@@ -679,6 +1660,11 @@ pub fn get_valid_burn_amounts(contract: &CardsContract) -> &Vec<u128> {
     &contract.config.valid_burn_amounts
 }
 
+/// Get valid bet amounts
+pub fn get_valid_bet_amounts(contract: &CardsContract) -> &Vec<u128> {
+    &contract.config.valid_bet_amounts
+}
+
 /// Get contract configuration
 pub fn get_config(contract: &CardsContract) -> &ContractConfig {
     &contract.config
@@ -690,53 +1676,201 @@ pub fn update_config(contract: &mut CardsContract, update: AdminConfigUpdate) {
     let caller = env::predecessor_account_id();
     require!(caller == contract.owner_id, "Only contract owner can call this method");
     
-    let timestamp = env::block_timestamp();
-    
     if let Some(new_amount) = update.daily_claim_amount {
+        let min_amount = MIN_DAILY_CLAIM_AMOUNT * card_unit(contract);
+        require!(
+            new_amount >= min_amount,
+            format!("Daily claim amount must be at least {}", min_amount)
+        );
         let old_amount = contract.config.daily_claim_amount;
         contract.config.daily_claim_amount = new_amount;
         
-        emit_event(CardEvent::ConfigUpdate {
-            field: "daily_claim_amount".to_string(),
-            old_value: old_amount.to_string(),
-            new_value: new_amount.to_string(),
-            updated_by: env::predecessor_account_id(),
-            timestamp,
-        });
+        record_config_change(contract, "daily_claim_amount", old_amount.to_string(), new_amount.to_string());
     }
     
     if let Some(new_interval) = update.claim_interval {
         let old_interval = contract.config.claim_interval;
         contract.config.claim_interval = new_interval;
         
-        emit_event(CardEvent::ConfigUpdate {
-            field: "claim_interval".to_string(),
-            old_value: format!("{}s", old_interval / 1_000_000_000),
-            new_value: format!("{}s", new_interval / 1_000_000_000),
-            updated_by: env::predecessor_account_id(),
-            timestamp,
-        });
+        record_config_change(contract, "claim_interval", format!("{}s", old_interval / 1_000_000_000), format!("{}s", new_interval / 1_000_000_000));
+    }
+    
+    if let Some(new_interval) = update.vip_claim_interval {
+        let old_interval = contract.config.vip_claim_interval;
+        contract.config.vip_claim_interval = new_interval;
+        
+        record_config_change(contract, "vip_claim_interval", format!("{}s", old_interval / 1_000_000_000), format!("{}s", new_interval / 1_000_000_000));
+    }
+    
+    if update.claim_interval.is_some() || update.vip_claim_interval.is_some() {
+        require!(
+            contract.config.vip_claim_interval <= contract.config.claim_interval,
+            "vip_claim_interval cannot be longer than claim_interval"
+        );
     }
     
     if let Some(new_rates) = update.purchase_rates {
+        validate_purchase_tiers(&new_rates);
         contract.config.purchase_rates = new_rates;
         
-        emit_event(CardEvent::ConfigUpdate {
-            field: "purchase_rates".to_string(),
-            old_value: "updated".to_string(),
-            new_value: "updated".to_string(),
-            updated_by: env::predecessor_account_id(),
-            timestamp,
-        });
+        record_config_change(contract, "purchase_rates", "updated".to_string(), "updated".to_string());
+    }
+    
+    if let Some(new_amounts) = update.valid_burn_amounts {
+        validate_burn_amounts(&new_amounts, &contract.game_config);
+        let old_amounts = contract.config.valid_burn_amounts.clone();
+        contract.config.valid_burn_amounts = new_amounts;
+        
+        record_config_change(contract, "valid_burn_amounts", format!("{:?}", old_amounts), format!("{:?}", contract.config.valid_burn_amounts));
+    }
+    
+    if let Some(new_cap) = update.max_total_supply {
+        let old_cap = contract.config.max_total_supply;
+        contract.config.max_total_supply = Some(new_cap);
+        
+        record_config_change(contract, "max_total_supply", old_cap.map_or("unlimited".to_string(), |cap| cap.to_string()), new_cap.to_string());
+    }
+    
+    if let Some(new_fee) = update.transfer_fee_bps {
+        require!(
+            new_fee <= MAX_TRANSFER_FEE_BPS,
+            format!("Transfer fee cannot exceed {} bps", MAX_TRANSFER_FEE_BPS)
+        );
+        let old_fee = contract.config.transfer_fee_bps;
+        contract.config.transfer_fee_bps = new_fee;
+        
+        record_config_change(contract, "transfer_fee_bps", old_fee.to_string(), new_fee.to_string());
+    }
+    
+    if let Some(new_max) = update.max_catchup_claims {
+        require!(new_max >= 1, "max_catchup_claims must be at least 1");
+        let old_max = contract.config.max_catchup_claims;
+        contract.config.max_catchup_claims = new_max;
+        
+        record_config_change(contract, "max_catchup_claims", old_max.to_string(), new_max.to_string());
+    }
+    
+    if let Some(new_bonus) = update.referral_bonus_bps {
+        require!(
+            new_bonus <= MAX_REFERRAL_BONUS_BPS,
+            format!("Referral bonus cannot exceed {} bps", MAX_REFERRAL_BONUS_BPS)
+        );
+        let old_bonus = contract.config.referral_bonus_bps;
+        contract.config.referral_bonus_bps = new_bonus;
+        
+        record_config_change(contract, "referral_bonus_bps", old_bonus.to_string(), new_bonus.to_string());
+    }
+    
+    if let Some(chat_contract_id) = update.chat_contract_id {
+        let old_chat = contract.config.chat_contract_id.replace(chat_contract_id.clone());
+        record_config_change(contract, "chat_contract_id",
+            old_chat.map_or("none".to_string(), |id| id.to_string()), chat_contract_id.to_string());
+    }
+    
+    if let Some(new_amount) = update.chat_burn_amount {
+        require!(new_amount > 0, "chat_burn_amount must be positive");
+        let old_amount = contract.config.chat_burn_amount;
+        contract.config.chat_burn_amount = new_amount;
+        record_config_change(contract, "chat_burn_amount", old_amount.to_string(), new_amount.to_string());
+    }
+    
+    if let Some(new_overpay) = update.max_purchase_overpay {
+        let old_overpay = contract.config.max_purchase_overpay;
+        contract.config.max_purchase_overpay = new_overpay;
+        
+        record_config_change(contract, "max_purchase_overpay", old_overpay.to_string(), new_overpay.to_string());
+    }
+    
+    if let Some(enabled) = update.first_claim_immediate {
+        let old_enabled = contract.config.first_claim_immediate;
+        contract.config.first_claim_immediate = enabled;
+        
+        record_config_change(contract, "first_claim_immediate", old_enabled.to_string(), enabled.to_string());
+    }
+    
+    if update.claim_min.is_some() || update.claim_max.is_some() {
+        let bound = |value: u128| if value == 0 { None } else { Some(value) };
+        let new_min = update.claim_min.map_or(contract.config.claim_min, bound);
+        let new_max = update.claim_max.map_or(contract.config.claim_max, bound);
+        if let (Some(min), Some(max)) = (new_min, new_max) {
+            let min_amount = MIN_DAILY_CLAIM_AMOUNT * card_unit(contract);
+            require!(
+                min >= min_amount,
+                format!("claim_min must be at least {}", min_amount)
+            );
+            require!(min <= max, "claim_min cannot exceed claim_max");
+        }
+        
+        let describe = |bound: Option<u128>| bound.map_or("none".to_string(), |value| value.to_string());
+        let old_min = std::mem::replace(&mut contract.config.claim_min, new_min);
+        let old_max = std::mem::replace(&mut contract.config.claim_max, new_max);
+        if update.claim_min.is_some() {
+            record_config_change(contract, "claim_min", describe(old_min), describe(new_min));
+        }
+        if update.claim_max.is_some() {
+            record_config_change(contract, "claim_max", describe(old_max), describe(new_max));
+        }
     }
     
     log!("Contract configuration updated by {}", env::predecessor_account_id());
 }
 
+/// Insert a purchase tier at its position by NEAR cost (Owner only)
+pub fn add_purchase_tier(contract: &mut CardsContract, tier: PurchaseTier) {
+    let caller = env::predecessor_account_id();
+    require!(caller == contract.owner_id, "Only contract owner can call this method");
+    
+    let index = contract.config.purchase_rates
+        .partition_point(|existing| existing.near_cost < tier.near_cost);
+    let name = tier.name.clone();
+    contract.config.purchase_rates.insert(index, tier);
+    validate_purchase_tiers(&contract.config.purchase_rates);
+    
+    record_config_change(contract, "purchase_rates", "updated".to_string(), format!("added '{}' at index {}", name, index));
+}
+
+/// Remove the purchase tier at `index` (Owner only)
+pub fn remove_purchase_tier(contract: &mut CardsContract, index: u8) {
+    let caller = env::predecessor_account_id();
+    require!(caller == contract.owner_id, "Only contract owner can call this method");
+    require!(
+        (index as usize) < contract.config.purchase_rates.len(),
+        format!("Invalid tier index {}", index)
+    );
+    require!(contract.config.purchase_rates.len() > 1, "At least one purchase tier required");
+    
+    let removed = contract.config.purchase_rates.remove(index as usize);
+    
+    record_config_change(contract, "purchase_rates", "updated".to_string(), format!("removed '{}' from index {}", removed.name, index));
+}
+
 // ========================================
 // INTERNAL HELPER FUNCTIONS
 // ========================================
 
+/// Append a config change to the on-chain audit log
+pub fn append_config_audit(contract: &mut CardsContract, field: &str, old_value: String, new_value: String) {
+    contract.config_audit.push(&ConfigChange {
+        field: field.to_string(),
+        old_value,
+        new_value,
+        by: env::predecessor_account_id(),
+        timestamp: env::block_timestamp(),
+    });
+}
+
+/// Audit a token config change and emit its ConfigUpdate event
+pub fn record_config_change(contract: &mut CardsContract, field: &str, old_value: String, new_value: String) {
+    append_config_audit(contract, field, old_value.clone(), new_value.clone());
+    emit_sequenced(contract, CardEvent::ConfigUpdate {
+        field: field.to_string(),
+        old_value,
+        new_value,
+        updated_by: env::predecessor_account_id(),
+        timestamp: env::block_timestamp(),
+    });
+}
+
 /// Check if user has sufficient storage deposited
 pub fn has_sufficient_storage(contract: &CardsContract, account_id: &AccountId) -> bool {
     use crate::storage::calculate_user_storage_cost;
@@ -746,20 +1880,104 @@ pub fn has_sufficient_storage(contract: &CardsContract, account_id: &AccountId)
     deposit.as_yoctonear() >= required.as_yoctonear()
 }
 
-/// Check if user can claim based on last claim time
-pub fn can_user_claim(contract: &CardsContract, account_id: &AccountId) -> bool {
-    if let Some(user) = contract.accounts.get(account_id) {
-        if !user.storage_deposited {
-            return false;
-        }
-        
-        let current_time = env::block_timestamp();
-        let time_since_last_claim = current_time - user.last_claim_time;
-        
-        time_since_last_claim >= contract.config.claim_interval
-    } else {
-        false
-    }
+/// Check if minting `amount` more cards would exceed `max_total_supply`
+pub fn is_supply_cap_reached(contract: &CardsContract, amount: u128) -> bool {
+    contract.config.max_total_supply.is_some_and(|cap| {
+        contract.total_supply.saturating_add(amount) > cap
+    })
+}
+
+/// Validate a new set of bet options: at least `MIN_VALID_BURN_AMOUNTS` positive,
+/// strictly increasing values, with at least one inside the min/max bet range
+pub fn validate_burn_amounts(amounts: &[u128], game_config: &crate::game::GameConfig) {
+    require!(
+        amounts.len() >= MIN_VALID_BURN_AMOUNTS,
+        format!("At least {} valid burn amounts required", MIN_VALID_BURN_AMOUNTS)
+    );
+    require!(amounts[0] > 0, "Burn amounts must be positive");
+    require!(
+        amounts.windows(2).all(|pair| pair[0] < pair[1]),
+        "Burn amounts must be distinct and sorted ascending"
+    );
+    require!(
+        amounts.iter().any(|amount| {
+            *amount >= game_config.min_bet_amount && *amount <= game_config.max_bet_amount
+        }),
+        format!("At least one burn amount must be between {} and {}",
+            game_config.min_bet_amount, game_config.max_bet_amount)
+    );
+}
+
+/// Validate bet denominations: non-empty, strictly increasing, all within the min/max bet range
+pub fn validate_bet_amounts(amounts: &[u128], game_config: &crate::game::GameConfig) {
+    require!(!amounts.is_empty(), "At least one bet amount required");
+    require!(
+        amounts.windows(2).all(|pair| pair[0] < pair[1]),
+        "Bet amounts must be distinct and sorted ascending"
+    );
+    require!(
+        amounts.iter().all(|amount| {
+            *amount >= game_config.min_bet_amount && *amount <= game_config.max_bet_amount
+        }),
+        format!("Bet amounts must be between {} and {}",
+            game_config.min_bet_amount, game_config.max_bet_amount)
+    );
+}
+
+/// Validate purchase tiers: non-empty, NEAR cost strictly increasing,
+/// and a more expensive tier never grants fewer cards
+pub fn validate_purchase_tiers(tiers: &[PurchaseTier]) {
+    require!(!tiers.is_empty(), "At least one purchase tier required");
+    require!(
+        tiers.windows(2).all(|pair| pair[0].near_cost < pair[1].near_cost),
+        "Purchase tiers must have distinct NEAR costs sorted ascending"
+    );
+    require!(
+        tiers.windows(2).all(|pair| pair[0].cards_amount <= pair[1].cards_amount),
+        "Purchase tier cards must not decrease as NEAR cost increases"
+    );
+}
+
+/// Storage locked for an account: its UserAccount (including alias) plus any seat storage reserved while seated
+pub fn locked_storage(contract: &CardsContract, account_id: &AccountId) -> NearToken {
+    use crate::storage::{calculate_alias_storage_cost, calculate_referrer_storage_cost, calculate_user_storage_cost};
+    
+    let reserved = contract.seat_storage_reserved.get(account_id).unwrap_or(NearToken::from_near(0));
+    let user = contract.accounts.get(account_id);
+    let alias_cost = user.as_ref()
+        .and_then(|user| user.alias.as_deref())
+        .map_or(0, |alias| calculate_alias_storage_cost(alias).as_yoctonear());
+    let referrer_cost = user.as_ref()
+        .and_then(|user| user.referrer.as_ref())
+        .map_or(0, |referrer| calculate_referrer_storage_cost(referrer).as_yoctonear());
+    NearToken::from_yoctonear(
+        calculate_user_storage_cost(account_id).as_yoctonear() + reserved.as_yoctonear() + alias_cost + referrer_cost
+    )
+}
+
+/// Claim interval for a user's tier (purchasers get `vip_claim_interval`)
+pub fn claim_interval_for(contract: &CardsContract, user: &UserAccount) -> u64 {
+    if user.total_purchased > 0 {
+        contract.config.vip_claim_interval
+    } else {
+        contract.config.claim_interval
+    }
+}
+
+/// Check if user can claim based on last claim time
+pub fn can_user_claim(contract: &CardsContract, account_id: &AccountId) -> bool {
+    if let Some(user) = contract.accounts.get(account_id) {
+        if !user.storage_deposited {
+            return false;
+        }
+        
+        let current_time = env::block_timestamp();
+        let time_since_last_claim = current_time - user.last_claim_time;
+        
+        time_since_last_claim >= claim_interval_for(contract, &user)
+    } else {
+        false
+    }
 }
 
 // ========================================
@@ -767,7 +1985,7 @@ pub fn can_user_claim(contract: &CardsContract, account_id: &AccountId) -> bool
 // ========================================
 
 #[cfg(test)]
-pub mod tests {
+mod tests {
     use super::*;
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::{testing_env, VMContext};
@@ -777,6 +1995,7 @@ pub mod tests {
         VMContextBuilder::new()
             .current_account_id(accounts(0))
             .predecessor_account_id(predecessor)
+            .block_timestamp(MINUTE_IN_NS)
             .build()
     }
 
@@ -796,6 +2015,156 @@ pub mod tests {
         assert_eq!(contract.total_cards_claimed, 1000);
     }
 
+    #[test]
+    pub fn test_randomized_claim_within_range() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_min: Some(500),
+            claim_max: Some(1500),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        let mut total = 0;
+        let mut awards = Vec::new();
+        for round in 0..8u8 {
+            context.block_timestamp += MINUTE_IN_NS;
+            context.random_seed = [round.wrapping_mul(37).wrapping_add(11); 32];
+            testing_env!(context.clone());
+            let claimed = claim_daily_cards(&mut contract);
+            assert!((500..=1500).contains(&claimed), "award {} out of range", claimed);
+            total += claimed;
+            awards.push(claimed);
+        }
+        assert_eq!(get_balance(&contract, &accounts(1)), total);
+        assert!(awards.iter().any(|award| *award != awards[0]));
+    }
+
+    #[test]
+    pub fn test_claim_eligibility_reports_range() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_min: Some(500),
+            claim_max: Some(1500),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        let eligibility = check_claim_eligibility(&contract, &accounts(1));
+        assert!(eligibility.can_claim);
+        assert_eq!(eligibility.claim_amount, 1500);
+        assert_eq!(eligibility.claim_amount_min, 500);
+        
+        // A cap with room for the daily amount but not for claim_max pauses claims
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context.clone());
+        update_config(&mut contract, AdminConfigUpdate {
+            max_total_supply: Some(1200),
+            ..Default::default()
+        });
+        let eligibility = check_claim_eligibility(&contract, &accounts(1));
+        assert!(!eligibility.can_claim);
+        assert_eq!(eligibility.reason, CLAIMS_PAUSED_SUPPLY_CAP);
+    }
+
+    #[test]
+    pub fn test_claim_range_min_equals_max() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_min: Some(700),
+            claim_max: Some(700),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.random_seed = [0xAB; 32];
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        assert_eq!(claim_daily_cards(&mut contract), 700);
+        
+        // Clearing a bound restores the fixed daily amount
+        context.predecessor_account_id = accounts(0);
+        context.block_timestamp += MINUTE_IN_NS;
+        testing_env!(context.clone());
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_max: Some(0),
+            ..Default::default()
+        });
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        assert_eq!(claim_daily_cards(&mut contract), 1000);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            update_config(&mut contract, AdminConfigUpdate {
+                claim_min: Some(900),
+                claim_max: Some(800),
+                ..Default::default()
+            })
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_first_claim_immediate() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.block_timestamp = 10 * MINUTE_IN_NS;
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        assert!(check_claim_eligibility(&contract, &accounts(1)).can_claim);
+        assert_eq!(claim_daily_cards(&mut contract), 1000);
+    }
+
+    #[test]
+    pub fn test_first_claim_waits_one_interval() {
+        let mut context = get_context(accounts(0));
+        context.block_timestamp = 10 * MINUTE_IN_NS;
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            first_claim_immediate: Some(false),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        let eligibility = check_claim_eligibility(&contract, &accounts(1));
+        assert!(!eligibility.can_claim);
+        assert_eq!(eligibility.next_claim_time, 11 * MINUTE_IN_NS);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| claim_daily_cards(&mut contract)));
+        assert!(result.is_err());
+        
+        context.block_timestamp = 11 * MINUTE_IN_NS;
+        testing_env!(context);
+        assert_eq!(claim_daily_cards(&mut contract), 1000);
+    }
+
     #[test]
     pub fn test_purchase_cards_basic() {
         let mut context = get_context(accounts(1));
@@ -811,7 +2180,7 @@ pub mod tests {
         // Now purchase Basic Pack (tier 0) with direct NEAR payment
         context.attached_deposit = NearToken::from_near(1); // Exact tier cost
         testing_env!(context.clone());
-        let purchased = purchase_cards(&mut contract, 0);
+        let purchased = purchase_cards(&mut contract, 0, None);
         assert_eq!(purchased, 1000);
         assert_eq!(get_balance(&contract, &accounts(1)), 1000);
         assert_eq!(contract.total_cards_purchased, 1000);
@@ -833,4 +2202,1059 @@ pub mod tests {
         assert_eq!(contract.total_cards_burned, 10);
         assert_eq!(contract.total_supply, 990); // Supply reduced by burn
     }
+
+    fn burn_amounts_update(amounts: Vec<u128>) -> AdminConfigUpdate {
+        AdminConfigUpdate {
+            valid_burn_amounts: Some(amounts),
+            ..Default::default()
+        }
+    }
+
+    fn tier(near: u128, cards_amount: u128) -> PurchaseTier {
+        PurchaseTier {
+            near_cost: NearToken::from_near(near),
+            cards_amount,
+            name: format!("{} NEAR Pack", near),
+        }
+    }
+
+    #[test]
+    pub fn test_add_purchase_tier_in_middle() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        add_purchase_tier(&mut contract, tier(3, 4000));
+        
+        let costs: Vec<u128> = get_purchase_tiers(&contract).iter()
+            .map(|tier| tier.near_cost.as_near())
+            .collect();
+        assert_eq!(costs, vec![1, 2, 3, 5, 10]);
+        assert_eq!(get_tier_info(&contract, 2).unwrap().cards_amount, 4000);
+        
+        remove_purchase_tier(&mut contract, 2);
+        assert_eq!(get_purchase_tiers(&contract).len(), 4);
+        assert_eq!(get_tier_info(&contract, 2).unwrap().near_cost, NearToken::from_near(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Purchase tiers must have distinct NEAR costs sorted ascending")]
+    pub fn test_add_purchase_tier_duplicate_cost() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        add_purchase_tier(&mut contract, tier(2, 2500));
+    }
+
+    #[test]
+    #[should_panic(expected = "Purchase tier cards must not decrease as NEAR cost increases")]
+    pub fn test_add_purchase_tier_breaks_scaling() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        add_purchase_tier(&mut contract, tier(3, 1500));
+    }
+
+    #[test]
+    pub fn test_purchase_with_expected_cost() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        context.attached_deposit = NearToken::from_near(2);
+        testing_env!(context.clone());
+        assert_eq!(purchase_cards(&mut contract, 1, Some(NearToken::from_near(2))), 2200);
+        assert_eq!(get_balance(&contract, &accounts(1)), 2200);
+        
+        // Tier 0 is removed, so index 1 now points at the 5 NEAR pack
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        remove_purchase_tier(&mut contract, 0);
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_near(5);
+        testing_env!(context);
+        assert_eq!(purchase_cards(&mut contract, 1, Some(NearToken::from_near(2))), 0);
+        assert_eq!(get_balance(&contract, &accounts(1)), 2200);
+        
+        // The whole deposit goes back to the buyer and nothing reaches the owner
+        let receivers: Vec<AccountId> = near_sdk::test_utils::get_created_receipts().into_iter()
+            .map(|receipt| receipt.receiver_id)
+            .collect();
+        assert_eq!(receivers, vec![accounts(1)]);
+    }
+
+    #[test]
+    pub fn test_withdraw_treasury_tokens() {
+        let mut context = get_context(accounts(2));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        storage_deposit(&mut contract, Some(accounts(0)));
+        contract.treasury_id = accounts(2);
+        
+        // Only the owner can withdraw
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            withdraw_treasury_tokens(&mut contract, 100)
+        }));
+        assert!(result.is_err());
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        withdraw_treasury_tokens(&mut contract, 400);
+        assert_eq!(get_balance(&contract, &accounts(2)), 600);
+        assert_eq!(get_balance(&contract, &accounts(0)), 400);
+        assert_eq!(contract.total_supply, 1000);
+        
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            withdraw_treasury_tokens(&mut contract, 601)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_update_valid_burn_amounts() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, burn_amounts_update(vec![20, 40, 80, 200]));
+        assert_eq!(get_valid_burn_amounts(&contract), &vec![20, 40, 80, 200]);
+    }
+
+    #[test]
+    #[should_panic(expected = "At least 3 valid burn amounts required")]
+    pub fn test_update_valid_burn_amounts_single_value() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, burn_amounts_update(vec![50]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Burn amounts must be distinct and sorted ascending")]
+    pub fn test_update_valid_burn_amounts_unsorted() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, burn_amounts_update(vec![50, 10, 30]));
+    }
+
+    #[test]
+    #[should_panic(expected = "At least one burn amount must be between 10 and 1000")]
+    pub fn test_update_valid_burn_amounts_outside_bet_range() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, burn_amounts_update(vec![1, 2, 5000]));
+    }
+
+    #[test]
+    #[should_panic(expected = "daily claims paused: supply cap reached")]
+    pub fn test_claim_paused_at_supply_cap() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            max_total_supply: Some(1000),
+            ..Default::default()
+        });
+        
+        // Exhaust the cap with a Basic Pack purchase
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        purchase_cards(&mut contract, 0, None);
+        assert_eq!(contract.total_supply, 1000);
+        
+        let eligibility = check_claim_eligibility(&contract, &accounts(1));
+        assert!(!eligibility.can_claim);
+        assert_eq!(eligibility.reason, CLAIMS_PAUSED_SUPPLY_CAP);
+        
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        claim_daily_cards(&mut contract);
+    }
+
+    #[test]
+    pub fn test_update_daily_claim_amount() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            daily_claim_amount: Some(500),
+            ..Default::default()
+        });
+        assert_eq!(contract.config.daily_claim_amount, 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Daily claim amount must be at least 100")]
+    pub fn test_update_daily_claim_amount_too_low() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            daily_claim_amount: Some(1),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    pub fn test_gift_cards_with_note() {
+        let mut context = get_context(accounts(2));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None); // Receiver registers
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        let deposit_before = contract.storage_deposits.get(&accounts(1)).unwrap();
+        
+        gift_cards(&mut contract, accounts(2), 200, "Good luck at the table!".to_string());
+        gift_cards(&mut contract, accounts(2), 50, "Round two".to_string());
+        
+        assert_eq!(get_balance(&contract, &accounts(1)), 750);
+        assert_eq!(get_balance(&contract, &accounts(2)), 250);
+        assert_eq!(contract.total_supply, 1000); // Transfers don't change supply
+        assert!(contract.storage_deposits.get(&accounts(1)).unwrap() < deposit_before);
+        
+        let gifts = get_gifts_received(&contract, &accounts(2), None);
+        assert_eq!(gifts.len(), 2);
+        assert_eq!(gifts[0].note, "Round two"); // Newest first
+        assert_eq!(gifts[1].sender_id, accounts(1));
+        assert_eq!(gifts[1].amount, 200);
+        assert_eq!(get_gifts_received(&contract, &accounts(2), Some(1)).len(), 1);
+    }
+
+    #[test]
+    pub fn test_gift_event_reports_received_amount() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            transfer_fee_bps: Some(100),
+            ..Default::default()
+        });
+        
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        gift_cards(&mut contract, accounts(2), 500, "gg".to_string());
+        
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs.iter().any(|log| log.contains("\"Gift\"")
+            && log.contains("\"amount\":500")
+            && log.contains("\"received\":495")));
+        assert_eq!(get_gifts_received(&contract, &accounts(2), None)[0].amount, 495);
+        
+        // Oversized limits are clamped
+        for _ in 0..MAX_GIFTS_RECEIVED_LIMIT {
+            testing_env!(context.clone());
+            gift_cards(&mut contract, accounts(2), 1, String::new());
+        }
+        assert_eq!(
+            get_gifts_received(&contract, &accounts(2), Some(u64::MAX)).len() as u64,
+            MAX_GIFTS_RECEIVED_LIMIT
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Gift note too long")]
+    pub fn test_gift_note_too_long() {
+        let mut context = get_context(accounts(2));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        gift_cards(&mut contract, accounts(2), 10, "x".repeat(MAX_GIFT_NOTE_LENGTH + 1));
+    }
+
+    #[test]
+    pub fn test_supply_cap_blocks_minting_not_burning() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            max_total_supply: Some(2000),
+            ..Default::default()
+        });
+        assert_eq!(get_contract_stats(&contract).max_total_supply, Some(2000));
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        // Two Basic Packs reach the cap exactly
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        purchase_cards(&mut contract, 0, None);
+        purchase_cards(&mut contract, 0, None);
+        assert_eq!(contract.total_supply, 2000);
+        
+        // Next mint is rejected
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            purchase_cards(&mut contract, 0, None)
+        }));
+        assert!(result.is_err());
+        assert_eq!(contract.total_supply, 2000);
+        
+        // Burns still work
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        burn_cards(&mut contract, 100);
+        assert_eq!(contract.total_supply, 1900);
+    }
+
+    #[test]
+    pub fn test_transfer_fee_burned() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            transfer_fee_bps: Some(100),
+            ..Default::default()
+        });
+        
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        internal_transfer(&mut contract, &accounts(1), &accounts(2), 500);
+        
+        assert_eq!(get_balance(&contract, &accounts(1)), 500);
+        assert_eq!(get_balance(&contract, &accounts(2)), 495); // 1% fee
+        assert_eq!(contract.total_supply, 995);
+        
+        let stats = get_contract_stats(&contract);
+        assert_eq!(stats.transfer_fee_bps, 100);
+        assert_eq!(stats.total_transfer_fees_burned, 5);
+    }
+
+    #[test]
+    pub fn test_ft_transfer_with_memo() {
+        let mut context = get_context(accounts(2));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        context.attached_deposit = NearToken::from_yoctonear(1);
+        testing_env!(context);
+        ft_transfer(&mut contract, accounts(2), U128(300), Some("market order #7".to_string()));
+        
+        assert_eq!(get_balance(&contract, &accounts(1)), 700);
+        assert_eq!(get_balance(&contract, &accounts(2)), 300);
+        
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs.iter().any(|log| log.contains("\"standard\":\"nep141\"")
+            && log.contains("\"event\":\"ft_transfer\"")
+            && log.contains("\"memo\":\"market order #7\"")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    pub fn test_ft_transfer_requires_one_yocto() {
+        let mut context = get_context(accounts(2));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        ft_transfer(&mut contract, accounts(2), U128(300), None);
+    }
+
+    #[test]
+    pub fn test_vip_claim_interval_for_purchasers() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_interval: Some(DAY_IN_NS),
+            vip_claim_interval: Some(HOUR_IN_NS),
+            ..Default::default()
+        });
+        
+        // accounts(1) purchases, accounts(2) only claims
+        context.block_timestamp = DAY_IN_NS;
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        for account in [accounts(1), accounts(2)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            storage_deposit(&mut contract, None);
+            claim_daily_cards(&mut contract);
+        }
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        purchase_cards(&mut contract, 0, None);
+        
+        let vip = check_claim_eligibility(&contract, &accounts(1));
+        assert_eq!(vip.next_claim_time, DAY_IN_NS + HOUR_IN_NS);
+        let standard = check_claim_eligibility(&contract, &accounts(2));
+        assert_eq!(standard.next_claim_time, 2 * DAY_IN_NS);
+        
+        // After an hour only the purchaser can claim again
+        context.block_timestamp = DAY_IN_NS + HOUR_IN_NS;
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        assert!(check_claim_eligibility(&contract, &accounts(1)).can_claim);
+        assert!(!check_claim_eligibility(&contract, &accounts(2)).can_claim);
+        claim_daily_cards(&mut contract);
+        
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            claim_daily_cards(&mut contract)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_storage_unregister_clean_close() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        context.attached_deposit = NearToken::from_yoctonear(1);
+        testing_env!(context);
+        assert!(storage_unregister(&mut contract, None));
+        
+        assert!(contract.accounts.get(&accounts(1)).is_none());
+        assert!(contract.storage_deposits.get(&accounts(1)).is_none());
+        assert!(storage_balance_of(&contract, &accounts(1)).is_none());
+        
+        // Already closed
+        assert!(!storage_unregister(&mut contract, None));
+    }
+
+    #[test]
+    pub fn test_storage_unregister_requires_force_with_balance() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        context.attached_deposit = NearToken::from_yoctonear(1);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            storage_unregister(&mut contract, None)
+        }));
+        assert!(result.is_err());
+        assert_eq!(get_balance(&contract, &accounts(1)), 1000);
+        
+        // Forced close burns the balance
+        assert!(storage_unregister(&mut contract, Some(true)));
+        assert!(contract.accounts.get(&accounts(1)).is_none());
+        assert_eq!(contract.total_supply, 0);
+        assert_eq!(contract.total_cards_burned, 1000);
+    }
+
+    #[test]
+    pub fn test_claim_max_catch_up() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_interval: Some(DAY_IN_NS),
+            max_catchup_claims: Some(3),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.block_timestamp = DAY_IN_NS;
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        // Two and a half days missed: two intervals paid, the half day carries over
+        context.attached_deposit = NearToken::from_near(0);
+        context.block_timestamp = 3 * DAY_IN_NS + DAY_IN_NS / 2;
+        testing_env!(context.clone());
+        assert_eq!(claim_max(&mut contract), 2000);
+        assert_eq!(get_balance(&contract, &accounts(1)), 3000);
+        assert_eq!(contract.accounts.get(&accounts(1)).unwrap().last_claim_time, 3 * DAY_IN_NS);
+        
+        // Nothing more until the next full interval
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| claim_max(&mut contract)));
+        assert!(result.is_err());
+        
+        // Ten days missed: capped at three intervals
+        context.block_timestamp = 13 * DAY_IN_NS;
+        testing_env!(context);
+        assert_eq!(claim_max(&mut contract), 3000);
+        assert_eq!(get_balance(&contract, &accounts(1)), 6000);
+        assert_eq!(contract.accounts.get(&accounts(1)).unwrap().last_claim_time, 13 * DAY_IN_NS);
+        assert_eq!(contract.total_cards_claimed, 6000);
+    }
+
+    #[test]
+    pub fn test_claim_max_uses_claim_range() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_interval: Some(DAY_IN_NS),
+            max_catchup_claims: Some(5),
+            claim_min: Some(100),
+            claim_max: Some(200),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.block_timestamp = DAY_IN_NS;
+        context.random_seed = [7; 32];
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        let first = claim_daily_cards(&mut contract);
+        
+        // Five missed days: each interval is drawn from the range, never the fixed 1000
+        context.attached_deposit = NearToken::from_near(0);
+        context.block_timestamp = 6 * DAY_IN_NS;
+        testing_env!(context);
+        let caught_up = claim_max(&mut contract);
+        assert!((500..=1000).contains(&caught_up), "catch-up {} out of range", caught_up);
+        assert_eq!(get_balance(&contract, &accounts(1)), first + caught_up);
+        assert_eq!(contract.total_cards_claimed, first + caught_up);
+    }
+
+    #[test]
+    pub fn test_claim_max_new_account_single_claim() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        context.block_timestamp = 30 * DAY_IN_NS;
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        // No catch-up for the time before the account existed
+        assert_eq!(claim_max(&mut contract), 1000);
+        assert_eq!(contract.accounts.get(&accounts(1)).unwrap().last_claim_time, 30 * DAY_IN_NS);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| claim_max(&mut contract)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_prune_inactive_accounts() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        // accounts(3) is just as old but still holds cards
+        context.predecessor_account_id = accounts(3);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        // accounts(2) registers much later
+        context.predecessor_account_id = accounts(2);
+        context.block_timestamp = 10 * DAY_IN_NS;
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        context.block_timestamp = 10 * DAY_IN_NS + HOUR_IN_NS;
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        assert_eq!(prune_inactive_accounts(&mut contract, DAY_IN_NS, 0, 0).pruned, 0);
+        let result = prune_inactive_accounts(&mut contract, DAY_IN_NS, 0, 10);
+        assert_eq!(result.pruned, 1);
+        assert_eq!(result.next_index, None);
+        
+        assert!(contract.accounts.get(&accounts(1)).is_none());
+        assert!(contract.storage_deposits.get(&accounts(1)).is_none());
+        assert!(contract.accounts.get(&accounts(2)).is_some());
+        assert!(contract.accounts.get(&accounts(3)).is_some());
+        assert_eq!(prune_inactive_accounts(&mut contract, DAY_IN_NS, 0, 10).pruned, 0);
+    }
+
+    #[test]
+    pub fn test_prune_inactive_accounts_in_batches() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        // Stale, active, stale, active - in registration order
+        for (index, account_id) in [accounts(1), accounts(2), accounts(3), accounts(4)].into_iter().enumerate() {
+            context.predecessor_account_id = account_id;
+            testing_env!(context.clone());
+            storage_deposit(&mut contract, None);
+            if index % 2 == 1 {
+                claim_daily_cards(&mut contract);
+            }
+        }
+        
+        context.block_timestamp = 10 * DAY_IN_NS;
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context);
+        
+        // Each call examines at most `limit` accounts and hands back where to resume
+        let mut pruned = 0;
+        let mut batches = 0;
+        let mut from_index = Some(0);
+        while let Some(index) = from_index {
+            let result = prune_inactive_accounts(&mut contract, DAY_IN_NS, index, 1);
+            assert!(result.pruned <= 1);
+            pruned += result.pruned;
+            from_index = result.next_index;
+            batches += 1;
+        }
+        assert_eq!(pruned, 2);
+        assert_eq!(batches, 4);
+        assert!(contract.accounts.get(&accounts(1)).is_none());
+        assert!(contract.accounts.get(&accounts(3)).is_none());
+        assert_eq!(contract.accounts.len(), 2);
+    }
+
+    #[test]
+    pub fn test_storage_withdraw_to_receiver() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED + 300);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        // Transfer receipts only - the withdrawal callback goes back to the contract
+        let transfers = || near_sdk::test_utils::get_created_receipts().into_iter()
+            .map(|receipt| receipt.receiver_id)
+            .filter(|receiver_id| *receiver_id != accounts(0))
+            .collect::<Vec<_>>();
+        
+        // Withdrawing needs exactly 1 yoctoNEAR so a function-call key cannot redirect the deposit
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            storage_withdraw(&mut contract, None, Some(accounts(2)))
+        }));
+        assert!(result.is_err());
+        
+        // Self-withdraw goes back to the caller
+        context.attached_deposit = NearToken::from_yoctonear(1);
+        testing_env!(context.clone());
+        storage_withdraw(&mut contract, Some(NearToken::from_yoctonear(100)), None);
+        assert_eq!(transfers(), vec![accounts(1)]);
+        
+        // Redirected withdraw still comes out of the caller's deposit
+        testing_env!(context);
+        let balance = storage_withdraw(&mut contract, None, Some(accounts(2)));
+        assert_eq!(transfers(), vec![accounts(2)]);
+        assert_eq!(balance.available.as_yoctonear(), 0);
+        assert_eq!(contract.storage_deposits.get(&accounts(1)).unwrap(), locked_storage(&contract, &accounts(1)));
+        assert!(contract.storage_deposits.get(&accounts(2)).is_none());
+    }
+
+    #[test]
+    pub fn test_storage_withdraw_restored_on_failed_transfer() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        
+        context.attached_deposit = NearToken::from_yoctonear(1);
+        testing_env!(context.clone());
+        let balance = storage_withdraw(&mut contract, None, None);
+        let withdrawn = STORAGE_DEPOSIT_REQUIRED - balance.total.as_yoctonear();
+        assert!(withdrawn > 0);
+        
+        // Successful transfer keeps the deduction
+        context.predecessor_account_id = accounts(0);
+        let with_result = |result: PromiseResult| testing_env!(
+            context.clone(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+        with_result(PromiseResult::Successful(vec![]));
+        assert!(on_withdraw_complete(&mut contract, accounts(1), withdrawn));
+        assert_eq!(contract.storage_deposits.get(&accounts(1)).unwrap(), balance.total);
+        
+        // Failed transfer gives the deposit back
+        with_result(PromiseResult::Failed);
+        assert!(!on_withdraw_complete(&mut contract, accounts(1), withdrawn));
+        assert_eq!(contract.storage_deposits.get(&accounts(1)).unwrap().as_yoctonear(), STORAGE_DEPOSIT_REQUIRED);
+    }
+
+    #[test]
+    pub fn test_decimals_allow_exact_three_to_two_payout() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        // Whole cards: a natural on 15 loses the half card
+        assert_eq!(contract.game_config.expected_payout(15, crate::game::HandResult::Blackjack), 37);
+        
+        set_decimals(&mut contract, 2);
+        assert_eq!(crate::CardsContract::ft_metadata(&contract).decimals, 2);
+        assert_eq!(card_unit(&contract), 100);
+        assert_eq!(contract.config.daily_claim_amount, 1000 * 100);
+        assert_eq!(contract.game_config.min_bet_amount, 10 * 100);
+        assert!(contract.config.valid_bet_amounts.iter().all(|amount| amount % 100 == 0));
+        
+        // 15 cards at 2 decimals pays 37.50 cards exactly
+        let bet = 15 * card_unit(&contract);
+        assert_eq!(contract.game_config.expected_payout(bet, crate::game::HandResult::Blackjack), 3750);
+        
+        // Precision is fixed once cards exist
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        assert_eq!(claim_daily_cards(&mut contract), 100_000);
+        
+        context.predecessor_account_id = accounts(0);
+        testing_env!(context);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| set_decimals(&mut contract, 0)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn test_get_user_rank() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        for (account, claimed) in [(accounts(1), 3000), (accounts(2), 2000), (accounts(3), 1000)] {
+            context.predecessor_account_id = account.clone();
+            testing_env!(context.clone());
+            storage_deposit(&mut contract, None);
+            let mut user = contract.accounts.get(&account).unwrap();
+            user.total_claimed = claimed;
+            contract.accounts.insert(&account, &user);
+        }
+        
+        assert_eq!(get_user_rank(&contract, &accounts(1)), Some(1));
+        assert_eq!(get_user_rank(&contract, &accounts(2)), Some(2));
+        assert_eq!(get_user_rank(&contract, &accounts(3)), Some(3));
+        assert_eq!(get_user_rank(&contract, &accounts(4)), None);
+    }
+
+    #[test]
+    pub fn test_post_chat_with_burn() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            chat_contract_id: Some(accounts(4)),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        
+        let _ = post_chat_with_burn(&mut contract, "gg".to_string());
+        assert_eq!(get_balance(&contract, &accounts(1)), 990);
+        assert_eq!(contract.total_supply, 990);
+        
+        // Successful post keeps the burn
+        context.predecessor_account_id = accounts(0);
+        let with_result = |result: PromiseResult| testing_env!(
+            context.clone(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+        with_result(PromiseResult::Successful(vec![]));
+        assert!(on_chat_post_complete(&mut contract, accounts(1), 10));
+        assert_eq!(get_balance(&contract, &accounts(1)), 990);
+        
+        // Failed post re-mints the burn
+        with_result(PromiseResult::Failed);
+        assert!(!on_chat_post_complete(&mut contract, accounts(1), 10));
+        assert_eq!(get_balance(&contract, &accounts(1)), 1000);
+        assert_eq!(contract.total_supply, 1000);
+        assert_eq!(contract.total_cards_burned, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Chat bridge is not configured")]
+    pub fn test_post_chat_with_burn_requires_chat_contract() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context);
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        storage_deposit(&mut contract, None);
+        claim_daily_cards(&mut contract);
+        let _ = post_chat_with_burn(&mut contract, "gg".to_string());
+    }
+
+    #[test]
+    pub fn test_referral_bonus_paid_once() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            referral_bonus_bps: Some(1_000), // 10%
+            ..Default::default()
+        });
+        
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        for account in [accounts(1), accounts(2)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            storage_deposit(&mut contract, None);
+        }
+        
+        // accounts(2) was referred by accounts(1)
+        register_with_referrer(&mut contract, accounts(1));
+        assert_eq!(contract.accounts.get(&accounts(2)).unwrap().referrer, Some(accounts(1)));
+        
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        assert_eq!(purchase_cards(&mut contract, 0, None), 1000);
+        assert_eq!(get_balance(&contract, &accounts(1)), 100);
+        assert_eq!(contract.total_supply, 1100);
+        
+        // Later purchases pay no further bonus
+        assert_eq!(purchase_cards(&mut contract, 0, None), 1000);
+        assert_eq!(get_balance(&contract, &accounts(1)), 100);
+        assert_eq!(contract.total_supply, 2100);
+    }
+
+    #[test]
+    pub fn test_referral_rejects_self_and_loops() {
+        let mut context = get_context(accounts(1));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        for account in [accounts(1), accounts(2), accounts(3)] {
+            context.predecessor_account_id = account;
+            testing_env!(context.clone());
+            storage_deposit(&mut contract, None);
+        }
+        
+        // accounts(3) -> accounts(2) -> accounts(1)
+        register_with_referrer(&mut contract, accounts(2));
+        context.predecessor_account_id = accounts(2);
+        testing_env!(context.clone());
+        register_with_referrer(&mut contract, accounts(1));
+        
+        context.predecessor_account_id = accounts(1);
+        testing_env!(context);
+        for referrer in [accounts(1), accounts(3), accounts(4)] {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                register_with_referrer(&mut contract, referrer.clone())
+            }));
+            assert!(result.is_err(), "{} must be rejected", referrer);
+        }
+        assert!(contract.accounts.get(&accounts(1)).unwrap().referrer.is_none());
+    }
+
+    #[test]
+    pub fn test_purchase_overpay_tolerance() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        testing_env!(context.clone());
+        storage_deposit(&mut contract, None);
+        
+        // Exact cost
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        assert_eq!(purchase_cards(&mut contract, 0, None), 1000);
+        
+        // Transfers sent to the buyer by the last call
+        let refunds = || near_sdk::test_utils::get_created_receipts().into_iter()
+            .filter(|receipt| receipt.receiver_id == accounts(1))
+            .flat_map(|receipt| receipt.actions)
+            .filter_map(|action| match action {
+                near_sdk::mock::MockAction::Transfer { deposit, .. } => Some(deposit),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        
+        // Small excess within tolerance is accepted and the excess refunded
+        let overpay = contract.config.max_purchase_overpay;
+        context.attached_deposit = NearToken::from_near(1).saturating_add(overpay);
+        testing_env!(context.clone());
+        assert_eq!(purchase_cards(&mut contract, 0, None), 1000);
+        assert_eq!(get_balance(&contract, &accounts(1)), 2000);
+        assert_eq!(refunds(), vec![overpay]);
+        
+        // Paying for a much larger tier by mistake is rejected
+        context.attached_deposit = NearToken::from_near(10);
+        testing_env!(context.clone());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            purchase_cards(&mut contract, 0, None)
+        }));
+        assert!(result.is_err());
+        assert_eq!(get_balance(&contract, &accounts(1)), 2000);
+        assert_eq!(contract.total_cards_purchased, 2000);
+        
+        // The owner can raise the tolerance
+        context.predecessor_account_id = accounts(0);
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        update_config(&mut contract, AdminConfigUpdate {
+            max_purchase_overpay: Some(NearToken::from_near(9)),
+            ..Default::default()
+        });
+        
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_near(10);
+        testing_env!(context);
+        assert_eq!(purchase_cards(&mut contract, 0, None), 1000);
+        assert_eq!(refunds(), vec![NearToken::from_near(9)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "vip_claim_interval cannot be longer than claim_interval")]
+    pub fn test_vip_claim_interval_cannot_exceed_claim_interval() {
+        let context = get_context(accounts(0));
+        testing_env!(context);
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_interval: Some(DAY_IN_NS),
+            vip_claim_interval: Some(HOUR_IN_NS),
+            ..Default::default()
+        });
+        
+        // Shortening the standard interval below the VIP one is rejected
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_interval: Some(MINUTE_IN_NS),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    pub fn test_check_claim_eligibility_many() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.clone());
+        
+        let mut contract = crate::CardsContract::new(accounts(0));
+        update_config(&mut contract, AdminConfigUpdate {
+            claim_interval: Some(DAY_IN_NS),
+            vip_claim_interval: Some(HOUR_IN_NS),
+            ..Default::default()
+        });
+        
+        // accounts(1) and accounts(2) claim, accounts(3) only registers, accounts(4) is unknown
+        context.block_timestamp = DAY_IN_NS;
+        context.attached_deposit = NearToken::from_yoctonear(STORAGE_DEPOSIT_REQUIRED);
+        for account in [accounts(1), accounts(2), accounts(3)] {
+            context.predecessor_account_id = account.clone();
+            testing_env!(context.clone());
+            storage_deposit(&mut contract, None);
+            if account != accounts(3) {
+                claim_daily_cards(&mut contract);
+            }
+        }
+        context.predecessor_account_id = accounts(1);
+        context.attached_deposit = NearToken::from_near(1);
+        testing_env!(context.clone());
+        purchase_cards(&mut contract, 0, None);
+        
+        context.block_timestamp = DAY_IN_NS + MINUTE_IN_NS;
+        context.attached_deposit = NearToken::from_near(0);
+        testing_env!(context.clone());
+        let roster = check_claim_eligibility_many(&contract, vec![accounts(1), accounts(2), accounts(3), accounts(4)]);
+        assert_eq!(roster.len(), 4);
+        
+        assert_eq!(roster[0].account_id, accounts(1));
+        assert!(!roster[0].can_claim);
+        assert_eq!(roster[0].next_claim_time, DAY_IN_NS + HOUR_IN_NS);
+        assert!(roster[0].vip_interval);
+        
+        assert!(!roster[1].can_claim);
+        assert_eq!(roster[1].next_claim_time, 2 * DAY_IN_NS);
+        assert_eq!(roster[1].seconds_until_claim, (DAY_IN_NS - MINUTE_IN_NS) / 1_000_000_000);
+        assert!(!roster[1].vip_interval);
+        
+        assert!(roster[2].can_claim);
+        assert_eq!(roster[2].next_claim_time, context.block_timestamp);
+        assert!(!roster[2].vip_interval);
+        
+        assert!(!roster[3].can_claim);
+        assert_eq!(roster[3].next_claim_time, 0);
+        
+        // Oversized batches are rejected
+        let too_many = vec![accounts(1); MAX_ELIGIBILITY_BATCH + 1];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            check_claim_eligibility_many(&contract, too_many)
+        }));
+        assert!(result.is_err());
+    }
 }